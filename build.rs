@@ -21,6 +21,7 @@ fn main() {
     println!("cargo:rustc-check-cfg=cfg(error_generic_member_access)");
     println!("cargo:rustc-check-cfg=cfg(thiserror_nightly_testing)");
     println!("cargo:rustc-check-cfg=cfg(thiserror_no_backtrace_type)");
+    println!("cargo:rustc-check-cfg=cfg(thiserror_unredact)");
 
     let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
     let patch_version = env::var("CARGO_PKG_VERSION_PATCH").unwrap();
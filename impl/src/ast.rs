@@ -1,10 +1,13 @@
-use crate::attr::{self, Attrs};
+use crate::attr::{self, Attrs, Derive};
 use crate::generics::ParamsInScope;
 use crate::unraw::{IdentUnraw, MemberUnraw};
+use crate::Private;
 use proc_macro2::Span;
+use quote::ToTokens;
 use std::fmt::{self, Display};
 use syn::{
-    Data, DataEnum, DataStruct, DeriveInput, Error, Fields, Generics, Ident, Index, Result, Type,
+    Attribute, Data, DataEnum, DataStruct, DeriveInput, Error, Fields, Generics, Ident, Index,
+    Result, Type, Visibility,
 };
 
 pub enum Input<'a> {
@@ -13,14 +16,18 @@ pub enum Input<'a> {
 }
 
 pub struct Struct<'a> {
+    pub private: Private<'a>,
     pub attrs: Attrs<'a>,
+    pub vis: &'a Visibility,
     pub ident: Ident,
     pub generics: &'a Generics,
     pub fields: Vec<Field<'a>>,
 }
 
 pub struct Enum<'a> {
+    pub private: Private<'a>,
     pub attrs: Attrs<'a>,
+    pub vis: &'a Visibility,
     pub ident: Ident,
     pub generics: &'a Generics,
     pub variants: Vec<Variant<'a>>,
@@ -52,10 +59,10 @@ pub enum ContainerKind {
 }
 
 impl<'a> Input<'a> {
-    pub fn from_syn(node: &'a DeriveInput) -> Result<Self> {
+    pub fn from_syn(node: &'a DeriveInput, derive: Derive, private: Private<'a>) -> Result<Self> {
         match &node.data {
-            Data::Struct(data) => Struct::from_syn(node, data).map(Input::Struct),
-            Data::Enum(data) => Enum::from_syn(node, data).map(Input::Enum),
+            Data::Struct(data) => Struct::from_syn(node, data, derive, private).map(Input::Struct),
+            Data::Enum(data) => Enum::from_syn(node, data, derive, private).map(Input::Enum),
             Data::Union(_) => Err(Error::new_spanned(
                 node,
                 "union as errors are not supported",
@@ -65,16 +72,31 @@ impl<'a> Input<'a> {
 }
 
 impl<'a> Struct<'a> {
-    fn from_syn(node: &'a DeriveInput, data: &'a DataStruct) -> Result<Self> {
-        let mut attrs = attr::get(&node.attrs)?;
+    fn from_syn(
+        node: &'a DeriveInput,
+        data: &'a DataStruct,
+        derive: Derive,
+        private: Private<'a>,
+    ) -> Result<Self> {
+        let mut attrs = attr::get(&node.attrs, derive, private)?;
+        if let Some(doc) = attrs.doc {
+            attrs.display = Some(display_from_doc(doc, &node.attrs, doc, private)?);
+        }
         let scope = ParamsInScope::new(&node.generics);
-        let fields = Field::multiple_from_syn(&data.fields, &scope)?;
+        let fields = Field::multiple_from_syn(&data.fields, &scope, derive, private)?;
         if let Some(display) = &mut attrs.display {
             let container = ContainerKind::from_struct(data);
             display.expand_shorthand(&fields, container)?;
+            display.source_in_display &=
+                derive == Derive::Error && attrs.allow_source_in_display.is_none();
+            if attrs.lint_messages.is_none() {
+                display.style_warnings.clear();
+            }
         }
         Ok(Struct {
+            private,
             attrs,
+            vis: &node.vis,
             ident: node.ident.clone(),
             generics: &node.generics,
             fields,
@@ -83,14 +105,19 @@ impl<'a> Struct<'a> {
 }
 
 impl<'a> Enum<'a> {
-    fn from_syn(node: &'a DeriveInput, data: &'a DataEnum) -> Result<Self> {
-        let attrs = attr::get(&node.attrs)?;
+    fn from_syn(
+        node: &'a DeriveInput,
+        data: &'a DataEnum,
+        derive: Derive,
+        private: Private<'a>,
+    ) -> Result<Self> {
+        let attrs = attr::get(&node.attrs, derive, private)?;
         let scope = ParamsInScope::new(&node.generics);
         let variants = data
             .variants
             .iter()
             .map(|node| {
-                let mut variant = Variant::from_syn(node, &scope)?;
+                let mut variant = Variant::from_syn(node, &scope, derive, private)?;
                 if variant.attrs.display.is_none()
                     && variant.attrs.transparent.is_none()
                     && variant.attrs.fmt.is_none()
@@ -98,16 +125,28 @@ impl<'a> Enum<'a> {
                     variant.attrs.display.clone_from(&attrs.display);
                     variant.attrs.transparent = attrs.transparent;
                     variant.attrs.fmt.clone_from(&attrs.fmt);
+                    if let Some(doc) = attrs.doc {
+                        variant.attrs.display =
+                            Some(display_from_doc(doc, &node.attrs, node, private)?);
+                    }
                 }
                 if let Some(display) = &mut variant.attrs.display {
                     let container = ContainerKind::from_variant(node);
                     display.expand_shorthand(&variant.fields, container)?;
+                    display.source_in_display &= derive == Derive::Error
+                        && variant.attrs.allow_source_in_display.is_none()
+                        && attrs.allow_source_in_display.is_none();
+                    if attrs.lint_messages.is_none() {
+                        display.style_warnings.clear();
+                    }
                 }
                 Ok(variant)
             })
             .collect::<Result<_>>()?;
         Ok(Enum {
+            private,
             attrs,
+            vis: &node.vis,
             ident: node.ident.clone(),
             generics: &node.generics,
             variants,
@@ -116,30 +155,49 @@ impl<'a> Enum<'a> {
 }
 
 impl<'a> Variant<'a> {
-    fn from_syn(node: &'a syn::Variant, scope: &ParamsInScope<'a>) -> Result<Self> {
-        let attrs = attr::get(&node.attrs)?;
+    fn from_syn(
+        node: &'a syn::Variant,
+        scope: &ParamsInScope<'a>,
+        derive: Derive,
+        private: Private<'a>,
+    ) -> Result<Self> {
+        let mut attrs = attr::get(&node.attrs, derive, private)?;
+        if let Some(doc) = attrs.doc {
+            attrs.display = Some(display_from_doc(doc, &node.attrs, doc, private)?);
+        }
         Ok(Variant {
             original: node,
             attrs,
             ident: node.ident.clone(),
-            fields: Field::multiple_from_syn(&node.fields, scope)?,
+            fields: Field::multiple_from_syn(&node.fields, scope, derive, private)?,
         })
     }
 }
 
 impl<'a> Field<'a> {
-    fn multiple_from_syn(fields: &'a Fields, scope: &ParamsInScope<'a>) -> Result<Vec<Self>> {
+    fn multiple_from_syn(
+        fields: &'a Fields,
+        scope: &ParamsInScope<'a>,
+        derive: Derive,
+        private: Private<'a>,
+    ) -> Result<Vec<Self>> {
         fields
             .iter()
             .enumerate()
-            .map(|(i, field)| Field::from_syn(i, field, scope))
+            .map(|(i, field)| Field::from_syn(i, field, scope, derive, private))
             .collect()
     }
 
-    fn from_syn(i: usize, node: &'a syn::Field, scope: &ParamsInScope<'a>) -> Result<Self> {
+    fn from_syn(
+        i: usize,
+        node: &'a syn::Field,
+        scope: &ParamsInScope<'a>,
+        derive: Derive,
+        private: Private<'a>,
+    ) -> Result<Self> {
         Ok(Field {
             original: node,
-            attrs: attr::get(&node.attrs)?,
+            attrs: attr::get(&node.attrs, derive, private)?,
             member: match &node.ident {
                 Some(name) => MemberUnraw::Named(IdentUnraw::new(name.clone())),
                 None => MemberUnraw::Unnamed(Index {
@@ -153,6 +211,20 @@ impl<'a> Field<'a> {
     }
 }
 
+fn display_from_doc<'a>(
+    doc: &'a Attribute,
+    input: &'a [Attribute],
+    span: impl ToTokens,
+    private: Private<'a>,
+) -> Result<attr::Display<'a>> {
+    attr::display_from_doc(doc, input, private).ok_or_else(|| {
+        Error::new_spanned(
+            span,
+            "#[error(doc)] requires a doc comment to use as the display message",
+        )
+    })
+}
+
 impl ContainerKind {
     fn from_struct(node: &DataStruct) -> Self {
         match node.fields {
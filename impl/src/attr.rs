@@ -1,31 +1,57 @@
+use crate::Private;
 use proc_macro2::{Delimiter, Group, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
 use quote::{format_ident, quote, quote_spanned, ToTokens};
 use std::collections::BTreeSet as Set;
 use syn::parse::discouraged::Speculative;
 use syn::parse::{End, ParseStream};
+use syn::punctuated::Punctuated;
 use syn::{
-    braced, bracketed, parenthesized, token, Attribute, Error, ExprPath, Ident, Index, LitFloat,
-    LitInt, LitStr, Meta, Result, Token,
+    braced, bracketed, parenthesized, token, Attribute, Error, Expr, ExprLit, ExprPath, Ident,
+    Index, Lit, LitFloat, LitInt, LitStr, Macro, Meta, Path, Result, Token, Type, WherePredicate,
 };
 
 pub struct Attrs<'a> {
     pub display: Option<Display<'a>>,
     pub source: Option<Source<'a>>,
     pub backtrace: Option<&'a Attribute>,
+    pub location: Option<&'a Attribute>,
+    pub provide: Option<Provide<'a>>,
     pub from: Option<From<'a>>,
     pub transparent: Option<Transparent<'a>>,
     pub fmt: Option<Fmt<'a>>,
+    pub fmt_with: Option<Fmt<'a>>,
+    pub code: Option<Code<'a>>,
+    pub kind: Option<Kind<'a>>,
+    pub doc: Option<&'a Attribute>,
+    pub exit_code: Option<ExitCode<'a>>,
+    pub termination: Option<&'a Attribute>,
+    pub bound: Option<Bound>,
+    pub krate: Option<&'a Attribute>,
+    pub default: Option<Default<'a>>,
+    pub debug: Option<&'a Attribute>,
+    pub redact: Option<Redact<'a>>,
+    pub context: Option<&'a Attribute>,
+    pub constructors: Option<&'a Attribute>,
+    pub try_from: Option<&'a Attribute>,
+    pub accessors: Option<&'a Attribute>,
+    pub allow_source_in_display: Option<&'a Attribute>,
+    pub lint_messages: Option<&'a Attribute>,
 }
 
 #[derive(Clone)]
 pub struct Display<'a> {
     pub original: &'a Attribute,
+    pub private: Private<'a>,
     pub fmt: LitStr,
+    pub fmt_expr: Option<TokenStream>,
     pub args: TokenStream,
     pub requires_fmt_machinery: bool,
     pub has_bonus_display: bool,
     pub infinite_recursive: bool,
+    pub source_in_display: bool,
+    pub style_warnings: Vec<(Span, &'static str)>,
     pub implied_bounds: Set<(usize, Trait)>,
+    pub joined_fields: Set<usize>,
     pub bindings: Vec<(Ident, TokenStream)>,
 }
 
@@ -35,10 +61,13 @@ pub struct Source<'a> {
     pub span: Span,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 pub struct From<'a> {
     pub original: &'a Attribute,
     pub span: Span,
+    pub when: Option<ExprPath>,
+    pub types: Vec<Type>,
+    pub boxed: bool,
 }
 
 #[derive(Copy, Clone)]
@@ -53,6 +82,65 @@ pub struct Fmt<'a> {
     pub path: ExprPath,
 }
 
+#[derive(Clone)]
+pub struct Default<'a> {
+    pub original: &'a Attribute,
+    pub expr: Option<TokenStream>,
+}
+
+#[derive(Copy, Clone)]
+pub struct Provide<'a> {
+    pub original: &'a Attribute,
+    pub value: bool,
+}
+
+#[derive(Clone)]
+pub struct Code<'a> {
+    pub original: &'a Attribute,
+    pub lit: LitStr,
+}
+
+#[derive(Clone)]
+pub struct Kind<'a> {
+    pub original: &'a Attribute,
+    pub path: Path,
+}
+
+#[derive(Clone)]
+pub struct ExitCode<'a> {
+    pub original: &'a Attribute,
+    pub code: u8,
+}
+
+#[derive(Clone)]
+pub struct Redact<'a> {
+    pub original: &'a Attribute,
+    pub replacement: Option<LitStr>,
+}
+
+#[derive(Clone)]
+pub struct Bound {
+    pub error: Option<Vec<WherePredicate>>,
+    pub display: Option<Vec<WherePredicate>>,
+    pub from: Option<Vec<WherePredicate>>,
+}
+
+#[derive(Copy, Clone)]
+pub enum BoundImpl {
+    Error,
+    Display,
+    From,
+}
+
+// Which derive macro the attributes are being read for. The Display derive
+// takes the same arguments from #[display(...)] and ignores the helper
+// attributes that belong to the Error derive.
+#[derive(Copy, Clone, PartialEq)]
+pub enum Derive {
+    Error,
+    Display,
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
 pub enum Trait {
     Debug,
@@ -66,19 +154,42 @@ pub enum Trait {
     UpperExp,
 }
 
-pub fn get(input: &[Attribute]) -> Result<Attrs> {
+pub fn get<'a>(input: &'a [Attribute], derive: Derive, private: Private<'a>) -> Result<Attrs<'a>> {
     let mut attrs = Attrs {
         display: None,
         source: None,
         backtrace: None,
+        location: None,
+        provide: None,
         from: None,
         transparent: None,
         fmt: None,
+        fmt_with: None,
+        code: None,
+        kind: None,
+        doc: None,
+        exit_code: None,
+        termination: None,
+        bound: None,
+        krate: None,
+        default: None,
+        debug: None,
+        redact: None,
+        context: None,
+        constructors: None,
+        try_from: None,
+        accessors: None,
+        allow_source_in_display: None,
+        lint_messages: None,
     };
 
     for attr in input {
-        if attr.path().is_ident("error") {
-            parse_error_attribute(&mut attrs, attr)?;
+        if derive == Derive::Display {
+            if attr.path().is_ident("display") {
+                parse_error_attribute(&mut attrs, attr, private)?;
+            }
+        } else if attr.path().is_ident("error") {
+            parse_error_attribute(&mut attrs, attr, private)?;
         } else if attr.path().is_ident("source") {
             attr.meta.require_path_only()?;
             if attrs.source.is_some() {
@@ -97,14 +208,49 @@ pub fn get(input: &[Attribute]) -> Result<Attrs> {
                 return Err(Error::new_spanned(attr, "duplicate #[backtrace] attribute"));
             }
             attrs.backtrace = Some(attr);
+        } else if attr.path().is_ident("location") {
+            attr.meta.require_path_only()?;
+            if attrs.location.is_some() {
+                return Err(Error::new_spanned(attr, "duplicate #[location] attribute"));
+            }
+            attrs.location = Some(attr);
+        } else if attr.path().is_ident("provide") {
+            let value = if let Meta::Path(_) = attr.meta {
+                false
+            } else {
+                attr.meta
+                    .require_list()?
+                    .parse_args_with(|input: ParseStream| {
+                        syn::custom_keyword!(value);
+                        input.parse::<value>()?;
+                        Ok(true)
+                    })?
+            };
+            if attrs.provide.is_some() {
+                return Err(Error::new_spanned(attr, "duplicate #[provide] attribute"));
+            }
+            attrs.provide = Some(Provide {
+                original: attr,
+                value,
+            });
         } else if attr.path().is_ident("from") {
-            match attr.meta {
-                Meta::Path(_) => {}
+            let (when, boxed) = match &attr.meta {
+                Meta::Path(_) => (None, false),
+                Meta::List(list) if starts_with(&list.tokens, "when") => {
+                    (Some(list.parse_args_with(parse_from_when)?), false)
+                }
+                Meta::List(list) if starts_with(&list.tokens, "boxed") => {
+                    list.parse_args_with(|input: ParseStream| {
+                        syn::custom_keyword!(boxed);
+                        input.parse::<boxed>()
+                    })?;
+                    (None, true)
+                }
                 Meta::List(_) | Meta::NameValue(_) => {
                     // Assume this is meant for derive_more crate or something.
                     continue;
                 }
-            }
+            };
             if attrs.from.is_some() {
                 return Err(Error::new_spanned(attr, "duplicate #[from] attribute"));
             }
@@ -114,30 +260,130 @@ pub fn get(input: &[Attribute]) -> Result<Attrs> {
             attrs.from = Some(From {
                 original: attr,
                 span,
+                when,
+                types: Vec::new(),
+                boxed,
             });
         }
     }
 
+    if let Some(doc) = attrs.doc {
+        let name = doc.path().get_ident().unwrap();
+        if attrs.display.is_some() {
+            return Err(Error::new_spanned(
+                doc,
+                format!("cannot have both #[{}(doc)] and a display attribute", name),
+            ));
+        }
+        if attrs.transparent.is_some() {
+            return Err(Error::new_spanned(
+                doc,
+                format!("cannot have both #[{0}(doc)] and #[{0}(transparent)]", name),
+            ));
+        }
+        if attrs.fmt.is_some() {
+            return Err(Error::new_spanned(
+                doc,
+                format!("cannot have both #[{0}(doc)] and #[{0}(fmt = ...)]", name),
+            ));
+        }
+    }
+
     Ok(attrs)
 }
 
-fn parse_error_attribute<'a>(attrs: &mut Attrs<'a>, attr: &'a Attribute) -> Result<()> {
+// The display attribute implied by #[error(doc)], formed from the first
+// paragraph of the doc comment. This is the same paragraph that rustdoc shows
+// as the item's summary.
+pub fn display_from_doc<'a>(
+    doc: &'a Attribute,
+    input: &[Attribute],
+    private: Private<'a>,
+) -> Option<Display<'a>> {
+    let mut span = None;
+    let mut paragraph = Vec::new();
+    'attrs: for attr in input {
+        if !attr.path().is_ident("doc") {
+            continue;
+        }
+        let lit = match &attr.meta {
+            Meta::NameValue(meta) => match &meta.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(lit), ..
+                }) => lit,
+                _ => continue,
+            },
+            _ => continue,
+        };
+        span.get_or_insert_with(|| lit.span());
+        for line in lit.value().split('\n') {
+            let line = line.trim();
+            if !line.is_empty() {
+                paragraph.push(line.to_owned());
+            } else if !paragraph.is_empty() {
+                break 'attrs;
+            }
+        }
+    }
+
+    if paragraph.is_empty() {
+        return None;
+    }
+
+    Some(Display {
+        original: doc,
+        private,
+        fmt: LitStr::new(&paragraph.join(" "), span.unwrap()),
+        fmt_expr: None,
+        args: TokenStream::new(),
+        requires_fmt_machinery: false,
+        has_bonus_display: false,
+        infinite_recursive: false,
+        source_in_display: false,
+        style_warnings: Vec::new(),
+        implied_bounds: Set::new(),
+        joined_fields: Set::new(),
+        bindings: Vec::new(),
+    })
+}
+
+fn parse_error_attribute<'a>(
+    attrs: &mut Attrs<'a>,
+    attr: &'a Attribute,
+    private: Private<'a>,
+) -> Result<()> {
     mod kw {
         syn::custom_keyword!(transparent);
         syn::custom_keyword!(fmt);
+        syn::custom_keyword!(fmt_with);
+        syn::custom_keyword!(fmt_expr);
+        syn::custom_keyword!(code);
+        syn::custom_keyword!(kind);
+        syn::custom_keyword!(doc);
+        syn::custom_keyword!(exit_code);
+        syn::custom_keyword!(termination);
+        syn::custom_keyword!(bound);
+        syn::custom_keyword!(default);
+        syn::custom_keyword!(from);
+        syn::custom_keyword!(debug);
+        syn::custom_keyword!(display);
+        syn::custom_keyword!(redact);
+        syn::custom_keyword!(context);
+        syn::custom_keyword!(constructors);
+        syn::custom_keyword!(try_from);
+        syn::custom_keyword!(accessors);
+        syn::custom_keyword!(allow_source_in_display);
+        syn::custom_keyword!(lint_messages);
     }
 
     attr.parse_args_with(|input: ParseStream| {
         let lookahead = input.lookahead1();
-        let fmt = if lookahead.peek(LitStr) {
-            input.parse::<LitStr>()?
+        let (fmt, fmt_expr) = if lookahead.peek(LitStr) {
+            (input.parse::<LitStr>()?, None)
         } else if lookahead.peek(kw::transparent) {
             let kw: kw::transparent = input.parse()?;
             if attrs.transparent.is_some() {
-                return Err(Error::new_spanned(
-                    attr,
-                    "duplicate #[error(transparent)] attribute",
-                ));
+                return Err(duplicate(attr, "transparent"));
             }
             attrs.transparent = Some(Transparent {
                 original: attr,
@@ -149,16 +395,218 @@ fn parse_error_attribute<'a>(attrs: &mut Attrs<'a>, attr: &'a Attribute) -> Resu
             input.parse::<Token![=]>()?;
             let path: ExprPath = input.parse()?;
             if attrs.fmt.is_some() {
+                return Err(duplicate(attr, "fmt = ..."));
+            }
+            attrs.fmt = Some(Fmt {
+                original: attr,
+                path,
+            });
+            return Ok(());
+        } else if lookahead.peek(kw::fmt_with) {
+            input.parse::<kw::fmt_with>()?;
+            input.parse::<Token![=]>()?;
+            let path: ExprPath = input.parse()?;
+            if attrs.fmt_with.is_some() {
+                return Err(duplicate(attr, "fmt_with = ..."));
+            }
+            attrs.fmt_with = Some(Fmt {
+                original: attr,
+                path,
+            });
+            return Ok(());
+        } else if lookahead.peek(kw::code) {
+            input.parse::<kw::code>()?;
+            input.parse::<Token![=]>()?;
+            let lit: LitStr = input.parse()?;
+            if attrs.code.is_some() {
+                return Err(duplicate(attr, "code = ..."));
+            }
+            attrs.code = Some(Code {
+                original: attr,
+                lit,
+            });
+            return Ok(());
+        } else if lookahead.peek(kw::kind) {
+            input.parse::<kw::kind>()?;
+            input.parse::<Token![=]>()?;
+            let path: Path = input.parse()?;
+            if attrs.kind.is_some() {
+                return Err(duplicate(attr, "kind = ..."));
+            }
+            attrs.kind = Some(Kind {
+                original: attr,
+                path,
+            });
+            return Ok(());
+        } else if lookahead.peek(kw::doc) {
+            input.parse::<kw::doc>()?;
+            if attrs.doc.is_some() {
+                return Err(duplicate(attr, "doc"));
+            }
+            attrs.doc = Some(attr);
+            return Ok(());
+        } else if lookahead.peek(kw::default) {
+            input.parse::<kw::default>()?;
+            let expr = if input.is_empty() {
+                None
+            } else {
+                input.parse::<Token![=]>()?;
+                if input.is_empty() {
+                    return Err(input.error("expected an expression"));
+                }
+                Some(input.parse::<TokenStream>()?)
+            };
+            if attrs.default.is_some() {
+                return Err(duplicate(attr, "default"));
+            }
+            attrs.default = Some(Default {
+                original: attr,
+                expr,
+            });
+            return Ok(());
+        } else if lookahead.peek(kw::from) {
+            input.parse::<kw::from>()?;
+            let content;
+            parenthesized!(content in input);
+            let types = Punctuated::<Type, Token![,]>::parse_terminated(&content)?;
+            if types.is_empty() {
                 return Err(Error::new_spanned(
                     attr,
-                    "duplicate #[error(fmt = ...)] attribute",
+                    "expected at least one type to convert from",
                 ));
             }
-            attrs.fmt = Some(Fmt {
+            if attrs.from.is_some() {
+                return Err(Error::new_spanned(attr, "duplicate #[from] attribute"));
+            }
+            let span = (attr.pound_token.span)
+                .join(attr.bracket_token.span.join())
+                .unwrap_or(attr.path().get_ident().unwrap().span());
+            attrs.from = Some(From {
                 original: attr,
-                path,
+                span,
+                when: None,
+                types: types.into_iter().collect(),
+                boxed: false,
+            });
+            return Ok(());
+        } else if lookahead.peek(kw::exit_code) {
+            input.parse::<kw::exit_code>()?;
+            input.parse::<Token![=]>()?;
+            let lit: LitInt = input.parse()?;
+            let code = lit.base10_parse::<u8>()?;
+            if code == 0 {
+                return Err(Error::new_spanned(
+                    lit,
+                    "exit code 0 means success; an error needs a nonzero exit code",
+                ));
+            }
+            if attrs.exit_code.is_some() {
+                return Err(duplicate(attr, "exit_code = ..."));
+            }
+            attrs.exit_code = Some(ExitCode {
+                original: attr,
+                code,
+            });
+            return Ok(());
+        } else if lookahead.peek(kw::termination) {
+            input.parse::<kw::termination>()?;
+            if attrs.termination.is_some() {
+                return Err(duplicate(attr, "termination"));
+            }
+            attrs.termination = Some(attr);
+            return Ok(());
+        } else if lookahead.peek(kw::bound) {
+            input.parse::<kw::bound>()?;
+            let bound = parse_bound(input)?;
+            if attrs.bound.is_some() {
+                return Err(duplicate(attr, "bound = ..."));
+            }
+            attrs.bound = Some(bound);
+            return Ok(());
+        } else if input.peek(kw::debug) && input.peek2(Token![=]) {
+            input.parse::<kw::debug>()?;
+            input.parse::<Token![=]>()?;
+            input.parse::<kw::display>()?;
+            if attrs.debug.is_some() {
+                return Err(duplicate(attr, "debug = ..."));
+            }
+            attrs.debug = Some(attr);
+            return Ok(());
+        } else if lookahead.peek(kw::redact) {
+            input.parse::<kw::redact>()?;
+            let replacement = if input.is_empty() {
+                None
+            } else {
+                input.parse::<Token![=]>()?;
+                Some(input.parse::<LitStr>()?)
+            };
+            if attrs.redact.is_some() {
+                return Err(duplicate(attr, "redact"));
+            }
+            attrs.redact = Some(Redact {
+                original: attr,
+                replacement,
             });
             return Ok(());
+        } else if lookahead.peek(kw::context) {
+            input.parse::<kw::context>()?;
+            if attrs.context.is_some() {
+                return Err(duplicate(attr, "context"));
+            }
+            attrs.context = Some(attr);
+            return Ok(());
+        } else if lookahead.peek(kw::constructors) {
+            input.parse::<kw::constructors>()?;
+            if attrs.constructors.is_some() {
+                return Err(duplicate(attr, "constructors"));
+            }
+            attrs.constructors = Some(attr);
+            return Ok(());
+        } else if lookahead.peek(kw::try_from) {
+            input.parse::<kw::try_from>()?;
+            if attrs.try_from.is_some() {
+                return Err(duplicate(attr, "try_from"));
+            }
+            attrs.try_from = Some(attr);
+            return Ok(());
+        } else if lookahead.peek(kw::accessors) {
+            input.parse::<kw::accessors>()?;
+            if attrs.accessors.is_some() {
+                return Err(duplicate(attr, "accessors"));
+            }
+            attrs.accessors = Some(attr);
+            return Ok(());
+        } else if lookahead.peek(kw::allow_source_in_display) {
+            input.parse::<kw::allow_source_in_display>()?;
+            if attrs.allow_source_in_display.is_some() {
+                return Err(duplicate(attr, "allow_source_in_display"));
+            }
+            attrs.allow_source_in_display = Some(attr);
+            return Ok(());
+        } else if lookahead.peek(kw::lint_messages) {
+            input.parse::<kw::lint_messages>()?;
+            if attrs.lint_messages.is_some() {
+                return Err(duplicate(attr, "lint_messages"));
+            }
+            attrs.lint_messages = Some(attr);
+            return Ok(());
+        } else if lookahead.peek(Token![crate]) {
+            input.parse::<Token![crate]>()?;
+            input.parse::<Token![=]>()?;
+            input.parse::<Path>()?;
+            if attrs.krate.is_some() {
+                return Err(duplicate(attr, "crate = ..."));
+            }
+            attrs.krate = Some(attr);
+            return Ok(());
+        } else if lookahead.peek(kw::fmt_expr) {
+            input.parse::<kw::fmt_expr>()?;
+            input.parse::<Token![=]>()?;
+            let path = input.call(Path::parse_mod_style)?.into_token_stream();
+            let fmt = LitStr::new("", path.clone().into_iter().next().unwrap().span());
+            (fmt, Some(path))
+        } else if input.fork().parse::<Macro>().is_ok() {
+            parse_fmt_macro(input)?
         } else {
             return Err(lookahead.error());
         };
@@ -172,20 +620,29 @@ fn parse_error_attribute<'a>(attrs: &mut Attrs<'a>, attr: &'a Attribute) -> Resu
 
         let requires_fmt_machinery = !args.is_empty();
 
-        let display = Display {
+        let mut display = Display {
             original: attr,
+            private,
             fmt,
+            fmt_expr,
             args,
             requires_fmt_machinery,
             has_bonus_display: false,
             infinite_recursive: false,
+            source_in_display: false,
+            style_warnings: Vec::new(),
             implied_bounds: Set::new(),
+            joined_fields: Set::new(),
             bindings: Vec::new(),
         };
+        if display.fmt_expr.is_none() {
+            display.check_style();
+        }
         if attrs.display.is_some() {
+            let name = attr.path().get_ident().unwrap();
             return Err(Error::new_spanned(
                 attr,
-                "only one #[error(...)] attribute is allowed",
+                format!("only one #[{}(...)] attribute is allowed", name),
             ));
         }
         attrs.display = Some(display);
@@ -193,6 +650,176 @@ fn parse_error_attribute<'a>(attrs: &mut Attrs<'a>, attr: &'a Attribute) -> Resu
     })
 }
 
+// Refers to the attribute as written, which is #[display(...)] rather than
+// #[error(...)] for the Display derive.
+fn duplicate(attr: &Attribute, key: &str) -> Error {
+    let name = attr.path().get_ident().unwrap();
+    Error::new_spanned(attr, format!("duplicate #[{}({})] attribute", name, key))
+}
+
+// Picks out #[from(when = predicate)] and #[from(boxed)] among the #[from(...)]
+// attributes, which are otherwise left to derive_more.
+fn starts_with(tokens: &TokenStream, keyword: &str) -> bool {
+    match tokens.clone().into_iter().next() {
+        Some(TokenTree::Ident(ident)) => ident == keyword,
+        _ => false,
+    }
+}
+
+fn parse_from_when(input: ParseStream) -> Result<ExprPath> {
+    syn::custom_keyword!(when);
+    input.parse::<when>()?;
+    input.parse::<Token![=]>()?;
+    input.parse()
+}
+
+// Looked up ahead of parsing the rest of the attributes, so that the fallback
+// impls emitted for invalid input also refer to the right crate.
+pub fn crate_path(input: &[Attribute], derive: Derive) -> Option<Path> {
+    let ident = match derive {
+        Derive::Error => "error",
+        Derive::Display => "display",
+    };
+    input
+        .iter()
+        .filter(|attr| attr.path().is_ident(ident))
+        .find_map(|attr| {
+            attr.parse_args_with(|input: ParseStream| {
+                input.parse::<Token![crate]>()?;
+                input.parse::<Token![=]>()?;
+                input.parse::<Path>()
+            })
+            .ok()
+        })
+}
+
+// Either `bound = "..."` which applies to every generated impl, or
+// `bound(error = "...", display = "...", from = "...")` to give each impl its
+// own where-clause predicates.
+fn parse_bound(input: ParseStream) -> Result<Bound> {
+    let mut bound = Bound {
+        error: None,
+        display: None,
+        from: None,
+    };
+
+    if input.peek(Token![=]) {
+        input.parse::<Token![=]>()?;
+        let predicates = parse_predicates(input)?;
+        bound.error = Some(predicates.clone());
+        bound.display = Some(predicates.clone());
+        bound.from = Some(predicates);
+        return Ok(bound);
+    }
+
+    let content;
+    parenthesized!(content in input);
+    loop {
+        if content.is_empty() {
+            break;
+        }
+        let ident: Ident = content.parse()?;
+        let slot = if ident == "error" {
+            &mut bound.error
+        } else if ident == "display" {
+            &mut bound.display
+        } else if ident == "from" {
+            &mut bound.from
+        } else {
+            return Err(Error::new(
+                ident.span(),
+                "expected one of `error`, `display`, `from`",
+            ));
+        };
+        if slot.is_some() {
+            return Err(Error::new(
+                ident.span(),
+                format!("duplicate bound for the {} impl", ident),
+            ));
+        }
+        content.parse::<Token![=]>()?;
+        *slot = Some(parse_predicates(&content)?);
+        if content.is_empty() {
+            break;
+        }
+        content.parse::<Token![,]>()?;
+    }
+    Ok(bound)
+}
+
+fn parse_predicates(input: ParseStream) -> Result<Vec<WherePredicate>> {
+    let lit: LitStr = input.parse()?;
+    let predicates = lit.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+    Ok(predicates.into_iter().collect())
+}
+
+// A format string given as a macro call like concat!(...) or as a path to a
+// const. A concat! of literals is evaluated here so it can go through the usual
+// field shorthand. Anything else is left to be interpreted at runtime.
+fn parse_fmt_macro(input: ParseStream) -> Result<(LitStr, Option<TokenStream>)> {
+    let mac: Macro = input.parse()?;
+    if is_concat(&mac.path) {
+        if let Ok(value) = mac.parse_body_with(concat_args) {
+            let span = mac.path.segments[0].ident.span();
+            return Ok((LitStr::new(&value, span), None));
+        }
+    }
+    let expr = mac.into_token_stream();
+    let fmt = LitStr::new("", expr.clone().into_iter().next().unwrap().span());
+    Ok((fmt, Some(expr)))
+}
+
+fn concat_args(input: ParseStream) -> Result<String> {
+    let mut value = String::new();
+    while !input.is_empty() {
+        if input.peek(Token![-]) {
+            input.parse::<Token![-]>()?;
+            value.push('-');
+        }
+        let lookahead = input.lookahead1();
+        if lookahead.peek(Lit) {
+            match input.parse()? {
+                Lit::Str(lit) => value += &lit.value(),
+                Lit::Char(lit) => value.push(lit.value()),
+                Lit::Int(lit) => value += lit.base10_digits(),
+                Lit::Float(lit) => value += lit.base10_digits(),
+                Lit::Bool(lit) => value += if lit.value { "true" } else { "false" },
+                lit => return Err(Error::new_spanned(lit, "unsupported literal")),
+            }
+        } else if lookahead.peek(Ident) && input.peek2(Token![!]) {
+            let mac: Macro = input.parse()?;
+            if !is_concat(&mac.path) {
+                return Err(Error::new_spanned(mac, "unsupported macro"));
+            }
+            value += &mac.parse_body_with(concat_args)?;
+        } else {
+            return Err(lookahead.error());
+        }
+        if input.is_empty() {
+            break;
+        }
+        input.parse::<Token![,]>()?;
+    }
+    Ok(value)
+}
+
+// Matches `concat`, `core::concat` and `std::concat`, with or without a
+// leading `::`.
+fn is_concat(path: &Path) -> bool {
+    let mut segments = path.segments.iter().map(|segment| {
+        if segment.arguments.is_empty() {
+            segment.ident.to_string()
+        } else {
+            String::new()
+        }
+    });
+    match (segments.next(), segments.next(), segments.next()) {
+        (Some(first), None, None) => path.leading_colon.is_none() && first == "concat",
+        (Some(krate), Some(last), None) => (krate == "core" || krate == "std") && last == "concat",
+        _ => false,
+    }
+}
+
 fn parse_token_expr(input: ParseStream, mut begin_expr: bool) -> Result<TokenStream> {
     let mut tokens = Vec::new();
     while !input.is_empty() {
@@ -309,13 +936,23 @@ impl ToTokens for Display<'_> {
             });
         }
 
+        let private = self.private;
         let fmt = &self.fmt;
         let args = &self.args;
 
         // Currently `write!(f, "text")` produces less efficient code than
         // `f.write_str("text")`. We recognize the case when the format string
         // has no braces and no interpolated values, and generate simpler code.
-        let write = if self.requires_fmt_machinery {
+        let write = if let Some(fmt_expr) = &self.fmt_expr {
+            let locals = self.bindings.iter().map(|(local, _value)| local);
+            quote! {
+                #private::write_runtime_fmt(
+                    __formatter,
+                    #fmt_expr,
+                    &[#(&#locals as &dyn ::core::fmt::Display),*],
+                )
+            }
+        } else if self.requires_fmt_machinery {
             quote! {
                 ::core::write!(__formatter, #fmt #args)
             }
@@ -325,7 +962,7 @@ impl ToTokens for Display<'_> {
             }
         };
 
-        tokens.extend(if self.bindings.is_empty() {
+        let write = if self.bindings.is_empty() {
             write
         } else {
             let locals = self.bindings.iter().map(|(local, _value)| local);
@@ -335,7 +972,54 @@ impl ToTokens for Display<'_> {
                     (#(#locals,)*) => #write
                 }
             }
-        });
+        };
+
+        tokens.extend(write);
+    }
+}
+
+impl Display<'_> {
+    // Emitted apart from the formatting code, which the derive wraps in
+    // #[allow(deprecated)] so that deprecated fields can be formatted.
+    pub fn warnings(&self) -> TokenStream {
+        let mut warnings = Vec::new();
+        if self.source_in_display {
+            // Error reporters print the source after the message, so it would
+            // show up twice.
+            warnings.push(warning(
+                self.fmt.span(),
+                "source_in_display",
+                "the source error is also printed by error reporters after this message; remove it from the format string or add #[error(allow_source_in_display)]",
+            ));
+        }
+        for (span, note) in &self.style_warnings {
+            warnings.push(warning(*span, "message_style", note));
+        }
+        quote!(#(#warnings)*)
+    }
+}
+
+// Surfaces a warning at the given span through the deprecation lint, which is
+// the only way for a derive macro to emit one on stable.
+fn warning(span: Span, name: &str, note: &str) -> TokenStream {
+    let name = Ident::new(name, span);
+    quote_spanned! {span=>
+        {
+            #[deprecated(note = #note)]
+            fn #name() {}
+            #name();
+        }
+    }
+}
+
+impl Attrs<'_> {
+    pub fn bound(&self, which: BoundImpl) -> Option<&[WherePredicate]> {
+        let bound = self.bound.as_ref()?;
+        match which {
+            BoundImpl::Error => bound.error.as_deref(),
+            BoundImpl::Display => bound.display.as_deref(),
+            BoundImpl::From => bound.from.as_deref(),
+        }
     }
 }
 
@@ -1,26 +1,44 @@
-use crate::ast::{Enum, Field, Input, Struct};
-use crate::attr::Trait;
+use crate::ast::{Enum, Field, Input, Struct, Variant};
+use crate::attr::{self, Attrs, BoundImpl, Derive, ExitCode, Provide, Trait};
 use crate::fallback;
 use crate::generics::InferredBounds;
-use crate::private;
+use crate::prop::{type_parameter_of, type_parameter_of_option};
 use crate::unraw::MemberUnraw;
-use proc_macro2::{Ident, Span, TokenStream};
+use crate::Private;
+use proc_macro2::{Ident, Literal, Span, TokenStream};
 use quote::{format_ident, quote, quote_spanned, ToTokens};
 use std::collections::BTreeSet as Set;
-use syn::{DeriveInput, GenericArgument, PathArguments, Result, Token, Type};
+use syn::ext::IdentExt as _;
+use syn::spanned::Spanned as _;
+use syn::{DeriveInput, Generics, Result, Token, Type};
 
 pub fn derive(input: &DeriveInput) -> TokenStream {
-    match try_expand(input) {
+    let krate = attr::crate_path(&input.attrs, Derive::Error);
+    let private = Private {
+        krate: krate.as_ref(),
+    };
+    match try_expand(input, private) {
         Ok(expanded) => expanded,
         // If there are invalid attributes in the input, expand to an Error impl
         // anyway to minimize spurious secondary errors in other code that uses
         // this type as an Error.
-        Err(error) => fallback::expand(input, error),
+        Err(error) => fallback::expand(input, error, private),
+    }
+}
+
+pub fn derive_display(input: &DeriveInput) -> TokenStream {
+    let krate = attr::crate_path(&input.attrs, Derive::Display);
+    let private = Private {
+        krate: krate.as_ref(),
+    };
+    match try_expand_display(input, private) {
+        Ok(expanded) => expanded,
+        Err(error) => fallback::expand_display(input, error),
     }
 }
 
-fn try_expand(input: &DeriveInput) -> Result<TokenStream> {
-    let input = Input::from_syn(input)?;
+fn try_expand(input: &DeriveInput, private: Private) -> Result<TokenStream> {
+    let input = Input::from_syn(input, Derive::Error, private)?;
     input.validate()?;
     Ok(match input {
         Input::Struct(input) => impl_struct(input),
@@ -28,25 +46,40 @@ fn try_expand(input: &DeriveInput) -> Result<TokenStream> {
     })
 }
 
+fn try_expand_display(input: &DeriveInput, private: Private) -> Result<TokenStream> {
+    let input = Input::from_syn(input, Derive::Display, private)?;
+    input.validate_display()?;
+    Ok(match input {
+        Input::Struct(input) => struct_display_impl(&input),
+        Input::Enum(input) => enum_display_impl(&input),
+    }
+    .unwrap_or_default())
+}
+
 fn impl_struct(input: Struct) -> TokenStream {
+    let private = input.private;
     let ty = call_site_ident(&input.ident);
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
     let mut error_inferred_bounds = InferredBounds::new();
 
     let source_body = if let Some(transparent_attr) = &input.attrs.transparent {
         let only_field = &input.fields[0];
-        if only_field.contains_generic {
-            error_inferred_bounds.insert(only_field.ty, quote!(::thiserror::#private::Error));
+        if only_field.contains_generic
+            && infer_bound(BoundImpl::Error, &[&input.attrs, &only_field.attrs])
+        {
+            error_inferred_bounds.insert(only_field.ty, quote!(#private::Error));
         }
         let member = &only_field.member;
         Some(quote_spanned! {transparent_attr.span=>
-            ::thiserror::#private::Error::source(self.#member.as_dyn_error())
+            #private::Error::source(self.#member.as_dyn_error())
         })
     } else if let Some(source_field) = input.source_field() {
         let source = &source_field.member;
-        if source_field.contains_generic {
+        if source_field.contains_generic
+            && infer_bound(BoundImpl::Error, &[&input.attrs, &source_field.attrs])
+        {
             let ty = unoptional_type(source_field.ty);
-            error_inferred_bounds.insert(ty, quote!(::thiserror::#private::Error + 'static));
+            error_inferred_bounds.insert(ty, quote!(#private::Error + 'static));
         }
         let asref = if type_is_option(source_field.ty) {
             Some(quote_spanned!(source.span()=> .as_ref()?))
@@ -64,17 +97,41 @@ fn impl_struct(input: Struct) -> TokenStream {
     };
     let source_method = source_body.map(|body| {
         quote! {
-            fn source(&self) -> ::core::option::Option<&(dyn ::thiserror::#private::Error + 'static)> {
-                use ::thiserror::#private::AsDynError as _;
+            fn source(&self) -> ::core::option::Option<&(dyn #private::Error + 'static)> {
+                use #private::AsDynError as _;
                 #body
             }
         }
     });
 
-    let provide_method = input.backtrace_field().map(|backtrace_field| {
+    let provide_method = if input.backtrace_field().is_some() || input.has_provide() {
         let request = quote!(request);
-        let backtrace = &backtrace_field.member;
-        let body = if let Some(source_field) = input.source_field() {
+        let location_field = input.location_field();
+        let field_provide = input.fields.iter().filter_map(|field| {
+            let provide = field.attrs.provide?;
+            if location_field.is_some_and(|location_field| location_field.member == field.member) {
+                return None;
+            }
+            if field.contains_generic
+                && infer_bound(BoundImpl::Error, &[&input.attrs, &field.attrs])
+            {
+                let ty = unoptional_type(field.ty);
+                error_inferred_bounds.insert(ty, provide_bound(provide));
+            }
+            let member = &field.member;
+            Some(provide_field(
+                field,
+                provide,
+                quote!(&self.#member),
+                &request,
+            ))
+        });
+        let field_provide = field_provide.collect::<Vec<_>>();
+        let location_provide = location_field.map(|location_field| {
+            let location = &location_field.member;
+            provide_location(quote!(&self.#location), &request, private)
+        });
+        let source_provide = input.source_field().map(|source_field| {
             let source = &source_field.member;
             let source_provide = if type_is_option(source_field.ty) {
                 quote_spanned! {source.span()=>
@@ -87,138 +144,178 @@ fn impl_struct(input: Struct) -> TokenStream {
                     self.#source.thiserror_provide(#request);
                 }
             };
-            let self_provide = if source == backtrace {
+            quote! {
+                use #private::ThiserrorProvide as _;
+                #source_provide
+            }
+        });
+        let self_provide = input.backtrace_field().and_then(|backtrace_field| {
+            let backtrace = &backtrace_field.member;
+            if input
+                .source_field()
+                .is_some_and(|source_field| source_field.member == *backtrace)
+            {
                 None
             } else if type_is_option(backtrace_field.ty) {
                 Some(quote! {
                     if let ::core::option::Option::Some(backtrace) = &self.#backtrace {
-                        #request.provide_ref::<::thiserror::#private::Backtrace>(backtrace);
+                        #request.provide_ref::<#private::Backtrace>(backtrace);
                     }
                 })
             } else {
                 Some(quote! {
-                    #request.provide_ref::<::thiserror::#private::Backtrace>(&self.#backtrace);
+                    #request.provide_ref::<#private::Backtrace>(&self.#backtrace);
                 })
-            };
-            quote! {
-                use ::thiserror::#private::ThiserrorProvide as _;
+            }
+        });
+        Some(quote! {
+            fn provide<'_request>(&'_request self, #request: &mut ::core::error::Request<'_request>) {
+                #(#field_provide)*
+                #location_provide
                 #source_provide
                 #self_provide
             }
-        } else if type_is_option(backtrace_field.ty) {
-            quote! {
-                if let ::core::option::Option::Some(backtrace) = &self.#backtrace {
-                    #request.provide_ref::<::thiserror::#private::Backtrace>(backtrace);
+        })
+    } else {
+        None
+    };
+
+    let display_impl = struct_display_impl(&input);
+
+    let from_impls = input.from_field().map(|from_field| {
+        let span = from_field.attrs.from.as_ref().unwrap().span;
+        let backtrace_field = input.distinct_backtrace_field();
+        let location_field = input.location_field();
+        let source_var = Ident::new("source", span);
+        let track_caller = location_field.map(|_| quote!(#[track_caller]));
+        let mut from_inferred_bounds = InferredBounds::new();
+        for field in &input.fields {
+            if let Some(default) = &field.attrs.default {
+                if default.expr.is_none()
+                    && field.contains_generic
+                    && infer_bound(BoundImpl::From, &[&input.attrs, &field.attrs])
+                {
+                    from_inferred_bounds.insert(field.ty, quote!(::core::default::Default));
                 }
             }
+        }
+        from_inferred_bounds.insert_explicit(input.explicit_bounds(BoundImpl::From));
+        let from_where_clause = from_inferred_bounds.augment_where_clause(input.generics);
+        let lint_allows = if input.generics.lifetimes().next().is_some() {
+            Some(quote! {
+                clippy::elidable_lifetime_names,
+                clippy::needless_lifetimes,
+            })
         } else {
+            None
+        };
+        let impl_from = |from: &Type, source: TokenStream| {
+            let body = from_initializer(
+                &input.fields,
+                from_field,
+                backtrace_field,
+                location_field,
+                source,
+                private,
+            );
+            let from_function = quote! {
+                #track_caller
+                fn from(#source_var: #from) -> Self {
+                    #ty #body
+                }
+            };
+            let from_impl = quote_spanned! {span=>
+                #[automatically_derived]
+                impl #impl_generics ::core::convert::From<#from> for #ty #ty_generics #from_where_clause {
+                    #from_function
+                }
+            };
             quote! {
-                #request.provide_ref::<::thiserror::#private::Backtrace>(&self.#backtrace);
+                #[allow(
+                    deprecated,
+                    unused_qualifications,
+                    clippy::redundant_field_names,
+                    #lint_allows
+                )]
+                #from_impl
             }
         };
-        quote! {
-            fn provide<'_request>(&'_request self, #request: &mut ::core::error::Request<'_request>) {
-                #body
+        let from_attr = from_field.attrs.from.as_ref().unwrap();
+        if from_attr.types.is_empty() {
+            let from = type_parameter_of_option(from_field.ty).unwrap_or(from_field.ty);
+            let from_impl = impl_from(from, quote!(#source_var));
+            let from_boxed_impl = from_field.from_pointee().map(|inner| {
+                impl_from(inner, quote!(::core::convert::From::from(#source_var)))
+            });
+            quote! {
+                #from_impl
+                #from_boxed_impl
+            }
+        } else {
+            let from_impls = from_attr.types.iter().map(|from| {
+                impl_from(from, quote!(::core::convert::Into::into(#source_var)))
+            });
+            quote! {
+                #(#from_impls)*
             }
         }
     });
 
-    let mut display_implied_bounds = Set::new();
-    let display_body = if input.attrs.transparent.is_some() {
-        let only_field = &input.fields[0].member;
-        display_implied_bounds.insert((0, Trait::Display));
-        Some(quote! {
-            ::core::fmt::Display::fmt(&self.#only_field, __formatter)
-        })
-    } else if let Some(display) = &input.attrs.display {
-        display_implied_bounds.clone_from(&display.implied_bounds);
-        let use_as_display = use_as_display(display.has_bonus_display);
-        let pat = fields_pat(&input.fields);
-        Some(quote! {
-            #use_as_display
-            #[allow(unused_variables, deprecated)]
-            let Self #pat = self;
-            #display
-        })
-    } else {
-        None
-    };
-    let display_impl = display_body.map(|body| {
-        let mut display_inferred_bounds = InferredBounds::new();
-        for (field, bound) in display_implied_bounds {
-            let field = &input.fields[field];
-            if field.contains_generic {
-                display_inferred_bounds.insert(field.ty, bound);
-            }
-        }
-        let display_where_clause = display_inferred_bounds.augment_where_clause(input.generics);
+    let code_impl = input.attrs.code.as_ref().map(|code| {
+        let vis = input.vis;
+        let code = &code.lit;
         quote! {
             #[allow(unused_qualifications)]
             #[automatically_derived]
-            impl #impl_generics ::core::fmt::Display for #ty #ty_generics #display_where_clause {
-                #[allow(clippy::used_underscore_binding)]
-                fn fmt(&self, __formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
-                    #body
+            impl #impl_generics #ty #ty_generics #where_clause {
+                /// Returns the stable error code of this error.
+                #vis fn code(&self) -> &'static str {
+                    #code
                 }
             }
         }
     });
 
-    let from_impl = input.from_field().map(|from_field| {
-        let span = from_field.attrs.from.unwrap().span;
-        let backtrace_field = input.distinct_backtrace_field();
-        let from = unoptional_type(from_field.ty);
-        let source_var = Ident::new("source", span);
-        let body = from_initializer(from_field, backtrace_field, &source_var);
-        let from_function = quote! {
-            fn from(#source_var: #from) -> Self {
-                #ty #body
-            }
-        };
-        let from_impl = quote_spanned! {span=>
-            #[automatically_derived]
-            impl #impl_generics ::core::convert::From<#from> for #ty #ty_generics #where_clause {
-                #from_function
-            }
-        };
-        let lint_allows = if input.generics.lifetimes().next().is_some() {
-            Some(quote! {
-                clippy::elidable_lifetime_names,
-                clippy::needless_lifetimes,
-            })
-        } else {
-            None
-        };
-        Some(quote! {
-            #[allow(
-                deprecated,
-                unused_qualifications,
-                #lint_allows
-            )]
-            #from_impl
-        })
+    let termination_impl = input.attrs.termination.map(|_| {
+        let exit_code = exit_code(input.attrs.exit_code.as_ref());
+        let body = quote!(#exit_code);
+        impl_termination(&ty, input.generics, body, private)
     });
 
+    let debug_impl = input
+        .attrs
+        .debug
+        .map(|_| impl_debug(&ty, input.generics, private));
+
     if input.generics.type_params().next().is_some() {
         let self_token = <Token![Self]>::default();
-        error_inferred_bounds.insert(self_token, Trait::Debug);
+        // The Debug impl from #[error(debug = display)] is itself conditional
+        // on the Error impl, so requiring Debug here would be circular.
+        if input.attrs.debug.is_none() {
+            error_inferred_bounds.insert(self_token, Trait::Debug);
+        }
         error_inferred_bounds.insert(self_token, Trait::Display);
     }
+    error_inferred_bounds.insert_explicit(input.explicit_bounds(BoundImpl::Error));
     let error_where_clause = error_inferred_bounds.augment_where_clause(input.generics);
 
     quote! {
         #[allow(unused_qualifications)]
         #[automatically_derived]
-        impl #impl_generics ::thiserror::#private::Error for #ty #ty_generics #error_where_clause {
+        impl #impl_generics #private::Error for #ty #ty_generics #error_where_clause {
             #source_method
             #provide_method
         }
         #display_impl
-        #from_impl
+        #debug_impl
+        #from_impls
+        #code_impl
+        #termination_impl
     }
 }
 
 fn impl_enum(input: Enum) -> TokenStream {
+    let private = input.private;
     let ty = call_site_ident(&input.ident);
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
     let mut error_inferred_bounds = InferredBounds::new();
@@ -228,21 +325,31 @@ fn impl_enum(input: Enum) -> TokenStream {
             let ident = &variant.ident;
             if let Some(transparent_attr) = &variant.attrs.transparent {
                 let only_field = &variant.fields[0];
-                if only_field.contains_generic {
-                    error_inferred_bounds.insert(only_field.ty, quote!(::thiserror::#private::Error));
+                if only_field.contains_generic
+                    && infer_bound(
+                        BoundImpl::Error,
+                        &[&input.attrs, &variant.attrs, &only_field.attrs],
+                    )
+                {
+                    error_inferred_bounds.insert(only_field.ty, quote!(#private::Error));
                 }
                 let member = &only_field.member;
                 let source = quote_spanned! {transparent_attr.span=>
-                    ::thiserror::#private::Error::source(transparent.as_dyn_error())
+                    #private::Error::source(transparent.as_dyn_error())
                 };
                 quote! {
                     #ty::#ident {#member: transparent} => #source,
                 }
             } else if let Some(source_field) = variant.source_field() {
                 let source = &source_field.member;
-                if source_field.contains_generic {
+                if source_field.contains_generic
+                    && infer_bound(
+                        BoundImpl::Error,
+                        &[&input.attrs, &variant.attrs, &source_field.attrs],
+                    )
+                {
                     let ty = unoptional_type(source_field.ty);
-                    error_inferred_bounds.insert(ty, quote!(::thiserror::#private::Error + 'static));
+                    error_inferred_bounds.insert(ty, quote!(#private::Error + 'static));
                 }
                 let asref = if type_is_option(source_field.ty) {
                     Some(quote_spanned!(source.span()=> .as_ref()?))
@@ -263,8 +370,8 @@ fn impl_enum(input: Enum) -> TokenStream {
             }
         });
         Some(quote! {
-            fn source(&self) -> ::core::option::Option<&(dyn ::thiserror::#private::Error + 'static)> {
-                use ::thiserror::#private::AsDynError as _;
+            fn source(&self) -> ::core::option::Option<&(dyn #private::Error + 'static)> {
+                use #private::AsDynError as _;
                 #[allow(deprecated)]
                 match self {
                     #(#arms)*
@@ -275,8 +382,57 @@ fn impl_enum(input: Enum) -> TokenStream {
         None
     };
 
-    let provide_method = if input.has_backtrace() {
+    let provide_method = if input.has_backtrace() || input.has_provide() {
         let request = quote!(request);
+        let field_provide = if input.has_location() || input.has_provide() {
+            let mut arms = Vec::new();
+            for variant in &input.variants {
+                let ident = &variant.ident;
+                let mut pat = Vec::new();
+                let mut provide = Vec::new();
+                let location_field = variant.location_field();
+                for (i, field) in variant.fields.iter().enumerate() {
+                    if location_field
+                        .is_some_and(|location_field| location_field.member == field.member)
+                    {
+                        continue;
+                    }
+                    if let Some(provide_attr) = field.attrs.provide {
+                        if field.contains_generic
+                            && infer_bound(
+                                BoundImpl::Error,
+                                &[&input.attrs, &variant.attrs, &field.attrs],
+                            )
+                        {
+                            let ty = unoptional_type(field.ty);
+                            error_inferred_bounds.insert(ty, provide_bound(provide_attr));
+                        }
+                        let member = &field.member;
+                        let var = format_ident!("provide{}", i);
+                        pat.push(quote!(#member: #var));
+                        provide.push(provide_field(field, provide_attr, quote!(#var), &request));
+                    }
+                }
+                if let Some(location_field) = location_field {
+                    let location = &location_field.member;
+                    pat.push(quote!(#location: location));
+                    provide.push(provide_location(quote!(location), &request, private));
+                }
+                arms.push(quote! {
+                    #ty::#ident {#(#pat,)* ..} => {
+                        #(#provide)*
+                    }
+                });
+            }
+            Some(quote! {
+                #[allow(deprecated)]
+                match self {
+                    #(#arms)*
+                }
+            })
+        } else {
+            None
+        };
         let arms = input.variants.iter().map(|variant| {
             let ident = &variant.ident;
             match (variant.backtrace_field(), variant.source_field()) {
@@ -300,12 +456,12 @@ fn impl_enum(input: Enum) -> TokenStream {
                     let self_provide = if type_is_option(backtrace_field.ty) {
                         quote! {
                             if let ::core::option::Option::Some(backtrace) = backtrace {
-                                #request.provide_ref::<::thiserror::#private::Backtrace>(backtrace);
+                                #request.provide_ref::<#private::Backtrace>(backtrace);
                             }
                         }
                     } else {
                         quote! {
-                            #request.provide_ref::<::thiserror::#private::Backtrace>(backtrace);
+                            #request.provide_ref::<#private::Backtrace>(backtrace);
                         }
                     };
                     quote! {
@@ -314,7 +470,7 @@ fn impl_enum(input: Enum) -> TokenStream {
                             #source: #varsource,
                             ..
                         } => {
-                            use ::thiserror::#private::ThiserrorProvide as _;
+                            use #private::ThiserrorProvide as _;
                             #source_provide
                             #self_provide
                         }
@@ -338,7 +494,7 @@ fn impl_enum(input: Enum) -> TokenStream {
                     };
                     quote! {
                         #ty::#ident {#backtrace: #varsource, ..} => {
-                            use ::thiserror::#private::ThiserrorProvide as _;
+                            use #private::ThiserrorProvide as _;
                             #source_provide
                         }
                     }
@@ -348,12 +504,12 @@ fn impl_enum(input: Enum) -> TokenStream {
                     let body = if type_is_option(backtrace_field.ty) {
                         quote! {
                             if let ::core::option::Option::Some(backtrace) = backtrace {
-                                #request.provide_ref::<::thiserror::#private::Backtrace>(backtrace);
+                                #request.provide_ref::<#private::Backtrace>(backtrace);
                             }
                         }
                     } else {
                         quote! {
-                            #request.provide_ref::<::thiserror::#private::Backtrace>(backtrace);
+                            #request.provide_ref::<#private::Backtrace>(backtrace);
                         }
                     };
                     quote! {
@@ -362,6 +518,29 @@ fn impl_enum(input: Enum) -> TokenStream {
                         }
                     }
                 }
+                (None, Some(source_field))
+                    if variant.location_field().is_some() || variant.has_provide() =>
+                {
+                    let source = &source_field.member;
+                    let varsource = quote!(source);
+                    let source_provide = if type_is_option(source_field.ty) {
+                        quote_spanned! {source.span()=>
+                            if let ::core::option::Option::Some(source) = #varsource {
+                                source.thiserror_provide(#request);
+                            }
+                        }
+                    } else {
+                        quote_spanned! {source.span()=>
+                            #varsource.thiserror_provide(#request);
+                        }
+                    };
+                    quote! {
+                        #ty::#ident {#source: #varsource, ..} => {
+                            use #private::ThiserrorProvide as _;
+                            #source_provide
+                        }
+                    }
+                }
                 (None, _) => quote! {
                     #ty::#ident {..} => {}
                 },
@@ -369,6 +548,7 @@ fn impl_enum(input: Enum) -> TokenStream {
         });
         Some(quote! {
             fn provide<'_request>(&'_request self, #request: &mut ::core::error::Request<'_request>) {
+                #field_provide
                 #[allow(deprecated)]
                 match self {
                     #(#arms)*
@@ -379,63 +559,147 @@ fn impl_enum(input: Enum) -> TokenStream {
         None
     };
 
-    let display_impl = if input.has_display() {
-        let mut display_inferred_bounds = InferredBounds::new();
-        let has_bonus_display = input.variants.iter().any(|v| {
-            v.attrs
-                .display
-                .as_ref()
-                .is_some_and(|display| display.has_bonus_display)
-        });
-        let use_as_display = use_as_display(has_bonus_display);
-        let void_deref = if input.variants.is_empty() {
-            Some(quote!(*))
+    let display_impl = enum_display_impl(&input);
+
+    let from_groups = input.from_groups().into_iter().flat_map(|group| {
+        let is_routed = group
+            .iter()
+            .any(|(_, field, _)| field.attrs.from.as_ref().unwrap().when.is_some());
+        if is_routed {
+            vec![group]
+        } else {
+            // Leave duplicate unconditional #[from] types to rustc's
+            // conflicting impls error.
+            group.into_iter().map(|entry| vec![entry]).collect()
+        }
+    });
+    let from_impls = from_groups.map(|group| {
+        // The unconditional #[from] variant, which goes last in the dispatch
+        // after any #[from(when = ...)] predicates in declaration order.
+        let (fallback, fallback_field, from) = *group
+            .iter()
+            .find(|(_, field, _)| field.attrs.from.as_ref().unwrap().when.is_none())
+            .unwrap_or(&group[0]);
+        let span = fallback_field.attrs.from.as_ref().unwrap().span;
+        let mut from_inferred_bounds = InferredBounds::new();
+        for (variant, _, _) in &group {
+            for field in &variant.fields {
+                if let Some(default) = &field.attrs.default {
+                    if default.expr.is_none()
+                        && field.contains_generic
+                        && infer_bound(
+                            BoundImpl::From,
+                            &[&input.attrs, &variant.attrs, &field.attrs],
+                        )
+                    {
+                        from_inferred_bounds.insert(field.ty, quote!(::core::default::Default));
+                    }
+                }
+            }
+        }
+        from_inferred_bounds.insert_explicit(input.attrs.bound(BoundImpl::From).unwrap_or_default());
+        for (variant, _, _) in &group {
+            from_inferred_bounds.insert_explicit(variant.explicit_bounds(BoundImpl::From));
+        }
+        let from_where_clause = from_inferred_bounds.augment_where_clause(input.generics);
+        let source_var = Ident::new("source", span);
+        let track_caller = group
+            .iter()
+            .any(|(variant, _, _)| variant.location_field().is_some())
+            .then(|| quote!(#[track_caller]));
+        let lint_allows = if input.generics.lifetimes().next().is_some() {
+            Some(quote! {
+                clippy::elidable_lifetime_names,
+                clippy::needless_lifetimes,
+            })
         } else {
             None
         };
-        let arms = input.variants.iter().map(|variant| {
-            let mut display_implied_bounds = Set::new();
-            let display = if let Some(display) = &variant.attrs.display {
-                display_implied_bounds.clone_from(&display.implied_bounds);
-                display.to_token_stream()
-            } else if let Some(fmt) = &variant.attrs.fmt {
-                let fmt_path = &fmt.path;
-                let vars = variant.fields.iter().map(|field| match &field.member {
-                    MemberUnraw::Named(ident) => ident.to_local(),
-                    MemberUnraw::Unnamed(index) => format_ident!("_{}", index),
-                });
-                quote!(#fmt_path(#(#vars,)* __formatter))
+        let construct = |variant: &Variant, from_field: &Field, source: &TokenStream| {
+            // Listed types in #[error(from(A, B))] convert into the field's type.
+            let source = if from_field.attrs.from.as_ref().unwrap().types.is_empty() {
+                source.clone()
             } else {
-                let only_field = match &variant.fields[0].member {
-                    MemberUnraw::Named(ident) => ident.to_local(),
-                    MemberUnraw::Unnamed(index) => format_ident!("_{}", index),
-                };
-                display_implied_bounds.insert((0, Trait::Display));
-                quote!(::core::fmt::Display::fmt(#only_field, __formatter))
+                quote!(::core::convert::Into::into(#source))
             };
-            for (field, bound) in display_implied_bounds {
-                let field = &variant.fields[field];
-                if field.contains_generic {
-                    display_inferred_bounds.insert(field.ty, bound);
+            let body = from_initializer(
+                &variant.fields,
+                from_field,
+                variant.distinct_backtrace_field(),
+                variant.location_field(),
+                source,
+                private,
+            );
+            let variant = &variant.ident;
+            quote!(#ty::#variant #body)
+        };
+        let impl_from = |from: &Type, source: TokenStream| {
+            let conditional_arms = group.iter().filter_map(|(variant, from_field, _)| {
+                let when = from_field.attrs.from.as_ref().unwrap().when.as_ref()?;
+                let value = construct(variant, from_field, &source);
+                Some(quote! {
+                    if #when(&#source_var) {
+                        return #value;
+                    }
+                })
+            });
+            let fallback_value = construct(fallback, fallback_field, &source);
+            let from_function = quote! {
+                #track_caller
+                fn from(#source_var: #from) -> Self {
+                    #(#conditional_arms)*
+                    #fallback_value
+                }
+            };
+            let from_impl = quote_spanned! {span=>
+                #[automatically_derived]
+                impl #impl_generics ::core::convert::From<#from> for #ty #ty_generics #from_where_clause {
+                    #from_function
                 }
+            };
+            quote! {
+                #[allow(
+                    deprecated,
+                    unused_qualifications,
+                    clippy::redundant_field_names,
+                    #lint_allows
+                )]
+                #from_impl
             }
+        };
+        let from_impl = impl_from(from, quote!(#source_var));
+        let from_boxed_impl = fallback_field.from_pointee().map(|inner| {
+            impl_from(inner, quote!(::core::convert::From::from(#source_var)))
+        });
+        quote! {
+            #from_impl
+            #from_boxed_impl
+        }
+    });
+
+    let code_impl = if input.has_code() {
+        let vis = input.vis;
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
+        } else {
+            None
+        };
+        let arms = input.variants.iter().map(|variant| {
             let ident = &variant.ident;
-            let pat = fields_pat(&variant.fields);
+            let code = &variant.attrs.code.as_ref().unwrap().lit;
             quote! {
-                #ty::#ident #pat => #display
+                #ty::#ident {..} => #code,
             }
         });
-        let arms = arms.collect::<Vec<_>>();
-        let display_where_clause = display_inferred_bounds.augment_where_clause(input.generics);
         Some(quote! {
             #[allow(unused_qualifications)]
             #[automatically_derived]
-            impl #impl_generics ::core::fmt::Display for #ty #ty_generics #display_where_clause {
-                fn fmt(&self, __formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
-                    #use_as_display
-                    #[allow(unused_variables, deprecated, clippy::used_underscore_binding)]
+            impl #impl_generics #ty #ty_generics #where_clause {
+                /// Returns the stable error code of this error.
+                #vis fn code(&self) -> &'static str {
+                    #[allow(deprecated)]
                     match #void_deref self {
-                        #(#arms,)*
+                        #(#arms)*
                     }
                 }
             }
@@ -444,59 +708,475 @@ fn impl_enum(input: Enum) -> TokenStream {
         None
     };
 
-    let from_impls = input.variants.iter().filter_map(|variant| {
-        let from_field = variant.from_field()?;
-        let span = from_field.attrs.from.unwrap().span;
-        let backtrace_field = variant.distinct_backtrace_field();
-        let variant = &variant.ident;
-        let from = unoptional_type(from_field.ty);
-        let source_var = Ident::new("source", span);
-        let body = from_initializer(from_field, backtrace_field, &source_var);
-        let from_function = quote! {
-            fn from(#source_var: #from) -> Self {
-                #ty::#variant #body
-            }
-        };
-        let from_impl = quote_spanned! {span=>
-            #[automatically_derived]
-            impl #impl_generics ::core::convert::From<#from> for #ty #ty_generics #where_clause {
-                #from_function
-            }
-        };
-        let lint_allows = if input.generics.lifetimes().next().is_some() {
-            Some(quote! {
-                clippy::elidable_lifetime_names,
-                clippy::needless_lifetimes,
-            })
+    let kind_impl = input.attrs.kind.as_ref().map(|kind| {
+        let vis = input.vis;
+        let kind_ty = &kind.path;
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
         } else {
             None
         };
-        Some(quote! {
-            #[allow(
-                deprecated,
-                unused_qualifications,
-                #lint_allows
+        let kind_variants = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let docs = (variant.original.attrs.iter()).filter(|attr| attr.path().is_ident("doc"));
+            match &variant.attrs.kind {
+                Some(inner_kind) => {
+                    let inner_kind = &inner_kind.path;
+                    quote!(#(#docs)* #ident(#inner_kind))
+                }
+                None => quote!(#(#docs)* #ident),
+            }
+        });
+        let arms = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            if variant.attrs.kind.is_some() {
+                let member = &variant.fields[0].member;
+                quote! {
+                    #ty::#ident {#member: transparent} => #kind_ty::#ident(transparent.kind()),
+                }
+            } else {
+                quote! {
+                    #ty::#ident {..} => #kind_ty::#ident,
+                }
+            }
+        });
+        let doc = format!("The kind of [`{}`], without any of its data.", input.ident);
+        quote! {
+            #[doc = #doc]
+            #[derive(
+                ::core::marker::Copy,
+                ::core::clone::Clone,
+                ::core::fmt::Debug,
+                ::core::cmp::PartialEq,
+                ::core::cmp::Eq,
+                ::core::hash::Hash,
             )]
-            #from_impl
-        })
+            #vis enum #kind_ty {
+                #(#kind_variants,)*
+            }
+
+            #[allow(unused_qualifications)]
+            #[automatically_derived]
+            impl #impl_generics #ty #ty_generics #where_clause {
+                /// Returns the kind of this error.
+                #vis fn kind(&self) -> #kind_ty {
+                    #[allow(deprecated)]
+                    match #void_deref self {
+                        #(#arms)*
+                    }
+                }
+            }
+        }
+    });
+
+    let context_impls = input.attrs.context.map(|_| {
+        let vis = input.vis;
+        let selectors = input.variants.iter().filter_map(|variant| {
+            if variant.attrs.transparent.is_some() {
+                return None;
+            }
+            let source_field = variant.source_field()?;
+            let backtrace_field = variant
+                .backtrace_field()
+                .filter(|backtrace_field| backtrace_field.member != source_field.member);
+            let location_field = variant.location_field();
+            let is_filled = |field: &Field| {
+                field.member == source_field.member
+                    || backtrace_field
+                        .is_some_and(|backtrace_field| backtrace_field.member == field.member)
+                    || location_field
+                        .is_some_and(|location_field| location_field.member == field.member)
+                    || field.attrs.default.is_some()
+            };
+            let context_fields: Vec<&Field> = variant
+                .fields
+                .iter()
+                .filter(|field| !is_filled(field))
+                .collect();
+            let ident = &variant.ident;
+            let selector = format_ident!("{}Ctx", ident.unraw(), span = ident.span());
+            let doc = format!("Context selector for [`{}::{}`].", input.ident, ident);
+            let selector_fields = context_fields.iter().map(|field| {
+                let docs = (field.original.attrs.iter()).filter(|attr| attr.path().is_ident("doc"));
+                let ty = field.ty;
+                match &field.original.ident {
+                    Some(ident) => quote!(#(#docs)* #vis #ident: #ty),
+                    None => quote!(#(#docs)* #vis #ty),
+                }
+            });
+            let selector_struct = if context_fields.is_empty() {
+                quote!(#vis struct #selector;)
+            } else if let Some(MemberUnraw::Named(_)) =
+                variant.fields.first().map(|field| &field.member)
+            {
+                quote!(#vis struct #selector { #(#selector_fields,)* })
+            } else {
+                quote!(#vis struct #selector(#(#selector_fields,)*);)
+            };
+            let context_initializer = context_fields.iter().enumerate().map(|(i, field)| {
+                let member = &field.member;
+                let selector_member = match member {
+                    MemberUnraw::Named(_) => member.to_token_stream(),
+                    MemberUnraw::Unnamed(_) => Literal::usize_unsuffixed(i).to_token_stream(),
+                };
+                quote!(#member: self.#selector_member,)
+            });
+            let source_initializer = source_initializer(
+                &variant.fields,
+                source_field,
+                backtrace_field,
+                location_field,
+                quote!(source),
+                private,
+            );
+            let source_ty = unoptional_type(source_field.ty);
+            let track_caller = location_field.map(|_| quote!(#[track_caller]));
+            Some(quote! {
+                #[doc = #doc]
+                #selector_struct
+
+                #[allow(
+                    deprecated,
+                    unused_qualifications,
+                    clippy::redundant_field_names,
+                )]
+                #[automatically_derived]
+                impl #private::IntoError<#source_ty> for #selector {
+                    type Error = #ty;
+
+                    #track_caller
+                    fn into_error(self, source: #source_ty) -> #ty {
+                        #ty::#ident {
+                            #source_initializer
+                            #(#context_initializer)*
+                        }
+                    }
+                }
+            })
+        });
+        quote! {
+            #(#selectors)*
+        }
+    });
+
+    let constructors_impl = input.attrs.constructors.map(|_| {
+        let vis = input.vis;
+        let constructors = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let source_field = variant.source_field();
+            let backtrace_field = variant.backtrace_field().filter(|backtrace_field| {
+                source_field.map_or(true, |source_field| {
+                    source_field.member != backtrace_field.member
+                })
+            });
+            let location_field = variant.location_field();
+            let is_captured = |field: &Field| {
+                backtrace_field
+                    .is_some_and(|backtrace_field| backtrace_field.member == field.member)
+                    || location_field
+                        .is_some_and(|location_field| location_field.member == field.member)
+                    || field.attrs.default.is_some()
+            };
+            let mut params = Vec::new();
+            let mut initializer = Vec::new();
+            for field in &variant.fields {
+                if is_captured(field) {
+                    continue;
+                }
+                let member = &field.member;
+                let var = match member {
+                    MemberUnraw::Named(ident) => ident.to_local(),
+                    MemberUnraw::Unnamed(index) => format_ident!("_{}", index),
+                };
+                let ty = field.ty;
+                params.push(quote!(#var: impl ::core::convert::Into<#ty>));
+                initializer.push(quote!(#member: ::core::convert::Into::into(#var),));
+            }
+            let captured =
+                captured_initializer(&variant.fields, backtrace_field, location_field, private);
+            let name = variant.snake_case_name();
+            // Keywords such as `type` or `match` become raw identifiers.
+            let mut constructor = syn::parse_str::<Ident>(&name)
+                .unwrap_or_else(|_| Ident::new_raw(&name, Span::call_site()));
+            constructor.set_span(ident.span());
+            let doc = format!("Constructs [`{}::{}`].", input.ident, ident);
+            let track_caller = location_field.map(|_| quote!(#[track_caller]));
+            quote! {
+                #[doc = #doc]
+                #track_caller
+                #vis fn #constructor(#(#params),*) -> Self {
+                    #ty::#ident {
+                        #(#initializer)*
+                        #captured
+                    }
+                }
+            }
+        });
+        quote! {
+            #[allow(
+                deprecated,
+                unused_qualifications,
+                clippy::redundant_field_names,
+            )]
+            #[automatically_derived]
+            impl #impl_generics #ty #ty_generics #where_clause {
+                #(#constructors)*
+            }
+        }
+    });
+
+    let mut try_from_groups: Vec<(String, Vec<&Variant>)> = Vec::new();
+    for variant in &input.variants {
+        if input.attrs.try_from.is_none() && variant.attrs.try_from.is_none() {
+            continue;
+        }
+        let from_field = match variant.from_field() {
+            Some(from_field) => from_field,
+            None => continue,
+        };
+        // Generic payloads would break the orphan rule, and boxed trait objects
+        // already convert from any error through the blanket TryFrom impl.
+        if from_field.contains_generic || type_is_boxed_trait_object(from_field.ty) {
+            continue;
+        }
+        let key = from_field.ty.to_token_stream().to_string();
+        match try_from_groups
+            .iter_mut()
+            .find(|(existing, _)| *existing == key)
+        {
+            Some((_, group)) => group.push(variant),
+            None => try_from_groups.push((key, vec![variant])),
+        }
+    }
+    let try_from_impls = try_from_groups.iter().map(|(_, group)| {
+        let payload = group[0].from_field().unwrap().ty;
+        let arms = group.iter().map(|variant| {
+            let ident = &variant.ident;
+            let member = &variant.from_field().unwrap().member;
+            quote! {
+                #ty::#ident {#member: source, ..} => ::core::result::Result::Ok(source),
+            }
+        });
+        quote! {
+            #[allow(unused_qualifications)]
+            #[automatically_derived]
+            impl #impl_generics ::core::convert::TryFrom<#ty #ty_generics> for #payload #where_clause {
+                type Error = #ty #ty_generics;
+
+                fn try_from(error: #ty #ty_generics) -> ::core::result::Result<Self, Self::Error> {
+                    #[allow(deprecated, unreachable_patterns)]
+                    match error {
+                        #(#arms)*
+                        error => ::core::result::Result::Err(error),
+                    }
+                }
+            }
+        }
+    });
+
+    let into_source_impl = input.attrs.try_from.map(|_| {
+        let vis = input.vis;
+        let mut into_source_inferred_bounds = InferredBounds::new();
+        let arms = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            match variant.source_field() {
+                Some(source_field) if variant.attrs.transparent.is_none() => {
+                    let member = &source_field.member;
+                    if source_field.contains_generic
+                        && infer_bound(
+                            BoundImpl::Error,
+                            &[&input.attrs, &variant.attrs, &source_field.attrs],
+                        )
+                    {
+                        let ty = unoptional_type(source_field.ty);
+                        into_source_inferred_bounds.insert(
+                            ty,
+                            quote!(#private::Error + ::core::marker::Send + ::core::marker::Sync + 'static),
+                        );
+                    }
+                    let boxed = if type_is_option(source_field.ty) {
+                        quote!(::core::option::Option::map(source, ::core::convert::From::from))
+                    } else {
+                        quote!(::core::option::Option::Some(::core::convert::From::from(source)))
+                    };
+                    quote! {
+                        #ty::#ident {#member: source, ..} => #boxed,
+                    }
+                }
+                _ => quote! {
+                    #ty::#ident {..} => ::core::option::Option::None,
+                },
+            }
+        });
+        let arms: Vec<TokenStream> = arms.collect();
+        let into_source_where_clause =
+            into_source_inferred_bounds.augment_where_clause(input.generics);
+        quote! {
+            #[allow(unused_qualifications)]
+            #[automatically_derived]
+            impl #impl_generics #ty #ty_generics #into_source_where_clause {
+                #private::into_source_method! {
+                    /// Returns the source of this error by value, if it has one.
+                    /// Transparent variants return `None`, since their source
+                    /// belongs to the error they wrap.
+                    #vis fn into_source(
+                        self,
+                    ) -> ::core::option::Option<#private::Box<dyn #private::Error + ::core::marker::Send + ::core::marker::Sync>> {
+                        #[allow(deprecated)]
+                        match self {
+                            #(#arms)*
+                        }
+                    }
+                }
+            }
+        }
     });
 
+    let accessors_impl = input.attrs.accessors.map(|_| {
+        let vis = input.vis;
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
+        } else {
+            None
+        };
+        let name_arms = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let name = ident.unraw().to_string();
+            quote! {
+                #ty::#ident {..} => #name,
+            }
+        });
+        let accessors = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let name = variant.snake_case_name();
+            let is_method = format_ident!("is_{}", name, span = ident.span());
+            let is_doc = format!("Returns `true` if this is [`{}::{}`].", input.ident, ident);
+            let is_accessor = quote! {
+                #[doc = #is_doc]
+                #vis fn #is_method(&self) -> bool {
+                    #[allow(deprecated)]
+                    ::core::matches!(self, #ty::#ident {..})
+                }
+            };
+            if variant.fields.is_empty() {
+                return is_accessor;
+            }
+            let as_method = format_ident!("as_{}", name, span = ident.span());
+            let as_doc = format!(
+                "Returns the {} of [`{}::{}`] if this is that variant.",
+                if variant.fields.len() == 1 {
+                    "field"
+                } else {
+                    "fields"
+                },
+                input.ident,
+                ident,
+            );
+            let members = variant.fields.iter().map(|field| &field.member);
+            let vars: Vec<Ident> = (0..variant.fields.len())
+                .map(|i| format_ident!("__field{}", i))
+                .collect();
+            let (ret, value) = if let [field] = variant.fields.as_slice() {
+                let ty = field.ty;
+                let var = &vars[0];
+                (quote!(&#ty), quote!(#var))
+            } else {
+                let tys = variant.fields.iter().map(|field| field.ty);
+                (quote!((#(&#tys),*)), quote!((#(#vars),*)))
+            };
+            quote! {
+                #is_accessor
+
+                #[doc = #as_doc]
+                #vis fn #as_method(&self) -> ::core::option::Option<#ret> {
+                    #[allow(deprecated)]
+                    match self {
+                        #ty::#ident {#(#members: #vars),*} => ::core::option::Option::Some(#value),
+                        #[allow(unreachable_patterns)]
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+        });
+        quote! {
+            #[allow(unused_qualifications)]
+            #[automatically_derived]
+            impl #impl_generics #ty #ty_generics #where_clause {
+                /// Returns the name of this error's variant.
+                #vis fn variant_name(&self) -> &'static str {
+                    #[allow(deprecated)]
+                    match #void_deref self {
+                        #(#name_arms)*
+                    }
+                }
+
+                #(#accessors)*
+            }
+        }
+    });
+
+    let termination_impl = input.attrs.termination.map(|_| {
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
+        } else {
+            None
+        };
+        let arms = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let exit_code = exit_code(
+                variant
+                    .attrs
+                    .exit_code
+                    .as_ref()
+                    .or(input.attrs.exit_code.as_ref()),
+            );
+            quote! {
+                #ty::#ident {..} => #exit_code,
+            }
+        });
+        let body = quote! {
+            match #void_deref &self {
+                #(#arms)*
+            }
+        };
+        impl_termination(&ty, input.generics, body, private)
+    });
+
+    let debug_impl = input
+        .attrs
+        .debug
+        .map(|_| impl_debug(&ty, input.generics, private));
+
     if input.generics.type_params().next().is_some() {
         let self_token = <Token![Self]>::default();
-        error_inferred_bounds.insert(self_token, Trait::Debug);
+        // The Debug impl from #[error(debug = display)] is itself conditional
+        // on the Error impl, so requiring Debug here would be circular.
+        if input.attrs.debug.is_none() {
+            error_inferred_bounds.insert(self_token, Trait::Debug);
+        }
         error_inferred_bounds.insert(self_token, Trait::Display);
     }
+    error_inferred_bounds.insert_explicit(input.explicit_bounds(BoundImpl::Error));
     let error_where_clause = error_inferred_bounds.augment_where_clause(input.generics);
 
     quote! {
         #[allow(unused_qualifications)]
         #[automatically_derived]
-        impl #impl_generics ::thiserror::#private::Error for #ty #ty_generics #error_where_clause {
+        impl #impl_generics #private::Error for #ty #ty_generics #error_where_clause {
             #source_method
             #provide_method
         }
         #display_impl
+        #debug_impl
         #(#from_impls)*
+        #code_impl
+        #kind_impl
+        #context_impls
+        #constructors_impl
+        #(#try_from_impls)*
+        #into_source_impl
+        #accessors_impl
+        #termination_impl
     }
 }
 
@@ -523,43 +1203,370 @@ fn fields_pat(fields: &[Field]) -> TokenStream {
     }
 }
 
-fn use_as_display(needs_as_display: bool) -> Option<TokenStream> {
+fn struct_display_impl(input: &Struct) -> Option<TokenStream> {
+    let ty = call_site_ident(&input.ident);
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let mut display_implied_bounds = Set::new();
+    let mut joined_fields = Set::new();
+    let display_body = if input.attrs.transparent.is_some() {
+        let only_field = &input.fields[0].member;
+        display_implied_bounds.insert((0, Trait::Display));
+        Some(quote! {
+            ::core::fmt::Display::fmt(&self.#only_field, __formatter)
+        })
+    } else if let Some(display) = &input.attrs.display {
+        display_implied_bounds.clone_from(&display.implied_bounds);
+        joined_fields.clone_from(&display.joined_fields);
+        let use_as_display = use_as_display(display.has_bonus_display, input.private);
+        let pat = fields_pat(&input.fields);
+        let warnings = display.warnings();
+        Some(quote! {
+            #use_as_display
+            #warnings
+            #[allow(unused_variables, deprecated)]
+            let Self #pat = self;
+            #display
+        })
+    } else {
+        None
+    };
+    display_body.map(|body| {
+        let mut display_inferred_bounds = InferredBounds::new();
+        for (field, bound) in display_implied_bounds {
+            let field = &input.fields[field];
+            if field.contains_generic
+                && infer_bound(BoundImpl::Display, &[&input.attrs, &field.attrs])
+            {
+                display_inferred_bounds.insert(field.ty, bound);
+            }
+        }
+        let private = input.private;
+        for field in joined_fields {
+            let field = &input.fields[field];
+            if field.contains_generic
+                && infer_bound(BoundImpl::Display, &[&input.attrs, &field.attrs])
+            {
+                display_inferred_bounds.insert(field.ty, quote!(#private::Joinable));
+            }
+        }
+        display_inferred_bounds.insert_explicit(input.explicit_bounds(BoundImpl::Display));
+        let display_where_clause = display_inferred_bounds.augment_where_clause(input.generics);
+        quote! {
+            #[allow(unused_qualifications)]
+            #[automatically_derived]
+            impl #impl_generics ::core::fmt::Display for #ty #ty_generics #display_where_clause {
+                #[allow(clippy::used_underscore_binding)]
+                fn fmt(&self, __formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                    #body
+                }
+            }
+        }
+    })
+}
+
+fn enum_display_impl(input: &Enum) -> Option<TokenStream> {
+    let ty = call_site_ident(&input.ident);
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    if input.has_display() {
+        let mut display_inferred_bounds = InferredBounds::new();
+        let has_bonus_display = input.variants.iter().any(|v| {
+            v.attrs
+                .display
+                .as_ref()
+                .is_some_and(|display| display.has_bonus_display)
+        });
+        let use_as_display = use_as_display(has_bonus_display, input.private);
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
+        } else {
+            None
+        };
+        let warnings = input
+            .variants
+            .iter()
+            .filter_map(|variant| variant.attrs.display.as_ref())
+            .map(attr::Display::warnings);
+        let arms = input.variants.iter().map(|variant| {
+            let mut display_implied_bounds = Set::new();
+            let mut joined_fields = Set::new();
+            let display = if let Some(display) = &variant.attrs.display {
+                display_implied_bounds.clone_from(&display.implied_bounds);
+                joined_fields.clone_from(&display.joined_fields);
+                display.to_token_stream()
+            } else if let Some(fmt) = &variant.attrs.fmt {
+                let fmt_path = &fmt.path;
+                let vars = variant.fields.iter().map(|field| match &field.member {
+                    MemberUnraw::Named(ident) => ident.to_local(),
+                    MemberUnraw::Unnamed(index) => format_ident!("_{}", index),
+                });
+                quote!(#fmt_path(#(#vars,)* __formatter))
+            } else {
+                let only_field = match &variant.fields[0].member {
+                    MemberUnraw::Named(ident) => ident.to_local(),
+                    MemberUnraw::Unnamed(index) => format_ident!("_{}", index),
+                };
+                display_implied_bounds.insert((0, Trait::Display));
+                quote!(::core::fmt::Display::fmt(#only_field, __formatter))
+            };
+            for (field, bound) in display_implied_bounds {
+                let field = &variant.fields[field];
+                if field.contains_generic
+                    && infer_bound(
+                        BoundImpl::Display,
+                        &[&input.attrs, &variant.attrs, &field.attrs],
+                    )
+                {
+                    display_inferred_bounds.insert(field.ty, bound);
+                }
+            }
+            let private = input.private;
+            for field in joined_fields {
+                let field = &variant.fields[field];
+                if field.contains_generic
+                    && infer_bound(
+                        BoundImpl::Display,
+                        &[&input.attrs, &variant.attrs, &field.attrs],
+                    )
+                {
+                    display_inferred_bounds.insert(field.ty, quote!(#private::Joinable));
+                }
+            }
+            let ident = &variant.ident;
+            let pat = fields_pat(&variant.fields);
+            quote! {
+                #ty::#ident #pat => #display
+            }
+        });
+        let arms = arms.collect::<Vec<_>>();
+        display_inferred_bounds.insert_explicit(input.explicit_bounds(BoundImpl::Display));
+        let display_where_clause = display_inferred_bounds.augment_where_clause(input.generics);
+        Some(quote! {
+            #[allow(unused_qualifications)]
+            #[automatically_derived]
+            impl #impl_generics ::core::fmt::Display for #ty #ty_generics #display_where_clause {
+                fn fmt(&self, __formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                    #use_as_display
+                    #(#warnings)*
+                    #[allow(unused_variables, deprecated, clippy::used_underscore_binding)]
+                    match #void_deref self {
+                        #(#arms,)*
+                    }
+                }
+            }
+        })
+    } else {
+        None
+    }
+}
+
+fn use_as_display(needs_as_display: bool, private: Private) -> Option<TokenStream> {
     if needs_as_display {
         Some(quote! {
-            use ::thiserror::#private::AsDisplay as _;
+            use #private::AsDisplay as _;
         })
     } else {
         None
     }
 }
 
+// Offers a #[provide] field through the request, by reference or, with
+// #[provide(value)], by copy. An Option field is provided only if it is Some.
+fn provide_field(
+    field: &Field,
+    provide: Provide,
+    value: TokenStream,
+    request: &TokenStream,
+) -> TokenStream {
+    let ty = unoptional_type(field.ty);
+    let span = provide.original.span();
+    match (type_is_option(field.ty), provide.value) {
+        (false, false) => quote_spanned! {span=>
+            #request.provide_ref::<#ty>(#value);
+        },
+        (false, true) => quote_spanned! {span=>
+            #request.provide_value::<#ty>(*#value);
+        },
+        (true, false) => quote_spanned! {span=>
+            if let ::core::option::Option::Some(provided) = #value {
+                #request.provide_ref::<#ty>(provided);
+            }
+        },
+        (true, true) => quote_spanned! {span=>
+            if let ::core::option::Option::Some(provided) = *#value {
+                #request.provide_value::<#ty>(provided);
+            }
+        },
+    }
+}
+
+fn provide_location(value: TokenStream, request: &TokenStream, private: Private) -> TokenStream {
+    quote! {
+        #private::provide_location(#request, #value);
+    }
+}
+
+fn provide_bound(provide: Provide) -> TokenStream {
+    if provide.value {
+        quote!(::core::marker::Copy + 'static)
+    } else {
+        quote!('static)
+    }
+}
+
+// Inferred bounds are left out wherever the user has written their own for the
+// same impl, whether on the field or on the variant or type containing it.
+fn infer_bound(which: BoundImpl, attrs: &[&Attrs]) -> bool {
+    attrs.iter().all(|attrs| attrs.bound(which).is_none())
+}
+
+// The process exit code for an error that has no #[error(exit_code = ...)] is 1,
+// the same as when main returns Err.
+fn exit_code(exit_code: Option<&ExitCode>) -> Literal {
+    Literal::u8_unsuffixed(exit_code.map_or(1, |exit_code| exit_code.code))
+}
+
+fn impl_termination(
+    ty: &Ident,
+    generics: &Generics,
+    exit_code: TokenStream,
+    private: Private,
+) -> TokenStream {
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let mut termination_inferred_bounds = InferredBounds::new();
+    if generics.type_params().next().is_some() {
+        termination_inferred_bounds.insert(quote!(#ty #ty_generics), quote!(#private::Error));
+    }
+    let termination_where_clause = termination_inferred_bounds.augment_where_clause(generics);
+    quote! {
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics #private::Termination for #ty #ty_generics #termination_where_clause {
+            fn report(self) -> #private::ExitCode {
+                #[allow(deprecated)]
+                let exit_code = #exit_code;
+                #private::report_exit(&self, exit_code)
+            }
+        }
+
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics ::core::convert::From<#ty #ty_generics> for #private::ExitCode #termination_where_clause {
+            fn from(error: #ty #ty_generics) -> Self {
+                #private::Termination::report(error)
+            }
+        }
+    }
+}
+
+fn impl_debug(ty: &Ident, generics: &Generics, private: Private) -> TokenStream {
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let mut debug_inferred_bounds = InferredBounds::new();
+    if generics.type_params().next().is_some() {
+        debug_inferred_bounds.insert(quote!(#ty #ty_generics), quote!(#private::Error));
+    }
+    let debug_where_clause = debug_inferred_bounds.augment_where_clause(generics);
+    quote! {
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics ::core::fmt::Debug for #ty #ty_generics #debug_where_clause {
+            fn fmt(&self, __formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                #private::debug_as_display(self, __formatter)
+            }
+        }
+    }
+}
+
 fn from_initializer(
+    fields: &[Field],
     from_field: &Field,
     backtrace_field: Option<&Field>,
-    source_var: &Ident,
+    location_field: Option<&Field>,
+    source: TokenStream,
+    private: Private,
+) -> TokenStream {
+    let initializer = source_initializer(
+        fields,
+        from_field,
+        backtrace_field,
+        location_field,
+        source,
+        private,
+    );
+    quote!({ #initializer })
+}
+
+// The fields which are filled in automatically when constructing an error from
+// its source: the source itself, backtrace, location, and #[error(default)].
+fn source_initializer(
+    fields: &[Field],
+    from_field: &Field,
+    backtrace_field: Option<&Field>,
+    location_field: Option<&Field>,
+    source: TokenStream,
+    private: Private,
 ) -> TokenStream {
     let from_member = &from_field.member;
     let some_source = if type_is_option(from_field.ty) {
-        quote!(::core::option::Option::Some(#source_var))
+        quote!(::core::option::Option::Some(#source))
     } else {
-        quote!(#source_var)
+        source
     };
+    let captured = captured_initializer(fields, backtrace_field, location_field, private);
+    quote! {
+        #from_member: #some_source,
+        #captured
+    }
+}
+
+// The backtrace, location, and #[error(default)] fields, none of which are
+// passed in by the caller.
+fn captured_initializer(
+    fields: &[Field],
+    backtrace_field: Option<&Field>,
+    location_field: Option<&Field>,
+    private: Private,
+) -> TokenStream {
     let backtrace = backtrace_field.map(|backtrace_field| {
         let backtrace_member = &backtrace_field.member;
         if type_is_option(backtrace_field.ty) {
             quote! {
-                #backtrace_member: ::core::option::Option::Some(::thiserror::#private::Backtrace::capture()),
+                #backtrace_member: ::core::option::Option::Some(#private::Backtrace::capture()),
             }
         } else {
             quote! {
-                #backtrace_member: ::core::convert::From::from(::thiserror::#private::Backtrace::capture()),
+                #backtrace_member: ::core::convert::From::from(#private::Backtrace::capture()),
             }
         }
     });
-    quote!({
-        #from_member: #some_source,
+    let location = location_field.map(|location_field| {
+        let location_member = &location_field.member;
+        if type_is_option(location_field.ty) {
+            quote! {
+                #location_member: ::core::option::Option::Some(::core::panic::Location::caller()),
+            }
+        } else {
+            quote! {
+                #location_member: ::core::convert::From::from(::core::panic::Location::caller()),
+            }
+        }
+    });
+    let defaults = fields.iter().filter_map(|field| {
+        let default = field.attrs.default.as_ref()?;
+        let member = &field.member;
+        Some(match &default.expr {
+            Some(expr) => quote!(#member: #expr,),
+            None => quote!(#member: ::core::default::Default::default(),),
+        })
+    });
+    quote! {
         #backtrace
-    })
+        #location
+        #(#defaults)*
+    }
+}
+
+fn type_is_boxed_trait_object(ty: &Type) -> bool {
+    matches!(type_parameter_of(ty, "Box"), Some(Type::TraitObject(_)))
 }
 
 fn type_is_option(ty: &Type) -> bool {
@@ -570,29 +1577,3 @@ fn unoptional_type(ty: &Type) -> TokenStream {
     let unoptional = type_parameter_of_option(ty).unwrap_or(ty);
     quote!(#unoptional)
 }
-
-fn type_parameter_of_option(ty: &Type) -> Option<&Type> {
-    let path = match ty {
-        Type::Path(ty) => &ty.path,
-        _ => return None,
-    };
-
-    let last = path.segments.last().unwrap();
-    if last.ident != "Option" {
-        return None;
-    }
-
-    let bracketed = match &last.arguments {
-        PathArguments::AngleBracketed(bracketed) => bracketed,
-        _ => return None,
-    };
-
-    if bracketed.args.len() != 1 {
-        return None;
-    }
-
-    match &bracketed.args[0] {
-        GenericArgument::Type(arg) => Some(arg),
-        _ => None,
-    }
-}
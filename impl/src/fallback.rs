@@ -1,26 +1,37 @@
 use crate::expand::call_site_ident;
-use crate::private;
+use crate::Private;
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::DeriveInput;
 
-pub(crate) fn expand(input: &DeriveInput, error: syn::Error) -> TokenStream {
+pub(crate) fn expand(input: &DeriveInput, error: syn::Error, private: Private) -> TokenStream {
     let ty = call_site_ident(&input.ident);
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
-    let error = error.to_compile_error();
+    let display_impl = expand_display(input, error);
 
     quote! {
-        #error
+        #display_impl
 
         #[allow(unused_qualifications)]
         #[automatically_derived]
-        impl #impl_generics ::thiserror::#private::Error for #ty #ty_generics #where_clause
+        impl #impl_generics #private::Error for #ty #ty_generics #where_clause
         where
             // Work around trivial bounds being unstable.
             // https://github.com/rust-lang/rust/issues/48214
             for<'workaround> #ty #ty_generics: ::core::fmt::Debug,
         {}
+    }
+}
+
+pub(crate) fn expand_display(input: &DeriveInput, error: syn::Error) -> TokenStream {
+    let ty = call_site_ident(&input.ident);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let error = error.to_compile_error();
+
+    quote! {
+        #error
 
         #[allow(unused_qualifications)]
         #[automatically_derived]
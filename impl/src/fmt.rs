@@ -1,12 +1,13 @@
 use crate::ast::{ContainerKind, Field};
 use crate::attr::{Display, Trait};
-use crate::private;
+use crate::prop::source_field;
 use crate::scan_expr::scan_expr;
 use crate::unraw::{IdentUnraw, MemberUnraw};
 use proc_macro2::{Delimiter, TokenStream, TokenTree};
 use quote::{format_ident, quote, quote_spanned, ToTokens as _};
 use std::collections::{BTreeSet, HashMap};
 use std::iter;
+use std::ops::Range;
 use syn::ext::IdentExt;
 use syn::parse::discouraged::Speculative;
 use syn::parse::{Error, ParseStream, Parser, Result};
@@ -14,12 +15,17 @@ use syn::{Expr, Ident, Index, LitStr, Token};
 
 impl Display<'_> {
     pub fn expand_shorthand(&mut self, fields: &[Field], container: ContainerKind) -> Result<()> {
+        if self.fmt_expr.is_some() {
+            return self.expand_runtime_args(fields);
+        }
+
         let raw_args = self.args.clone();
         let FmtArguments {
             named: user_named_args,
             first_unnamed,
         } = explicit_named_args.parse2(raw_args).unwrap();
 
+        let source = source_field(fields).map(|field| &field.member);
         let mut member_index = HashMap::new();
         let mut extra_positional_arguments_allowed = true;
         for (i, field) in fields.iter().enumerate() {
@@ -33,7 +39,9 @@ impl Display<'_> {
         let mut out = String::new();
         let mut has_bonus_display = false;
         let mut infinite_recursive = false;
+        let mut source_in_display = false;
         let mut implied_bounds = BTreeSet::new();
+        let mut joined_fields = BTreeSet::new();
         let mut bindings = Vec::new();
         let mut macro_named_args = BTreeSet::new();
 
@@ -103,7 +111,6 @@ impl Display<'_> {
                 Some(_) => Trait::Display,
                 None => {
                     bonus_display = true;
-                    has_bonus_display = true;
                     Trait::Display
                 }
             };
@@ -115,13 +122,54 @@ impl Display<'_> {
                     continue;
                 }
             };
-            implied_bounds.insert((field, bound));
-            let formatvar_prefix = if bonus_display {
-                "__display"
+            let redact = fields[field].attrs.redact.as_ref();
+            let fmt_with = fields[field].attrs.fmt_with.as_ref();
+            if fmt_with.is_some() && bound != Trait::Display && bound != Trait::Debug {
+                let msg =
+                    "a field with #[error(fmt_with = ...)] can only be formatted as `{}` or `{:?}`";
+                return Err(Error::new(span, msg));
+            }
+            let join = if read.starts_with(":join(") {
+                let separator = take_join_separator(&mut read).ok_or_else(|| {
+                    Error::new(span, "expected a separator string, as in `:join(\", \")`")
+                })?;
+                if fmt_with.is_some() {
+                    let msg = "cannot use `:join(...)` on a field with #[error(fmt_with = ...)]";
+                    return Err(Error::new(span, msg));
+                }
+                Some(separator)
+            } else {
+                None
+            };
+            if redact.is_none() {
+                source_in_display |= source == Some(&fields[field].member);
+            }
+            if join.is_some() {
+                bonus_display = false;
+                joined_fields.insert(field);
+            } else if fmt_with.is_some() {
+                bonus_display = false;
+            } else {
+                implied_bounds.insert((field, bound));
+            }
+            has_bonus_display |= bonus_display;
+            let formatvar_kind = if fmt_with.is_some() {
+                "fmt_with"
+            } else if join.is_some() {
+                "join"
+            } else if bonus_display {
+                "display"
             } else if bound == Trait::Pointer {
-                "__pointer"
+                "pointer"
             } else {
-                "__field"
+                "field"
+            };
+            // A redacted field gets a separate binding for each way it is
+            // wrapped, so that `{a:join(", ")}` and `{a}` do not share one.
+            let formatvar_prefix = if redact.is_some() {
+                format!("__redacted_{}", formatvar_kind)
+            } else {
+                format!("__{}", formatvar_kind)
             };
             let mut formatvar = IdentUnraw::new(match &member {
                 MemberUnraw::Unnamed(index) => format_ident!("{}{}", formatvar_prefix, index),
@@ -129,7 +177,9 @@ impl Display<'_> {
                     format_ident!("{}_{}", formatvar_prefix, ident.to_string())
                 }
             });
-            while user_named_args.contains(&formatvar) {
+            while user_named_args.contains(&formatvar)
+                || join.is_some() && macro_named_args.contains(&formatvar)
+            {
                 formatvar = IdentUnraw::new(format_ident!("_{}", formatvar.to_string()));
             }
             formatvar.set_span(span);
@@ -143,13 +193,40 @@ impl Display<'_> {
                 MemberUnraw::Named(ident) => ident.to_local(),
             };
             binding_value.set_span(span.resolved_at(fields[field].member.span()));
-            let wrapped_binding_value = if bonus_display {
+            let private = self.private;
+            let wrapped_binding_value = if let Some(separator) = &join {
+                quote_spanned!(span=> #private::Join(#binding_value, #separator))
+            } else if let Some(fmt_with) = fmt_with {
+                let path = &fmt_with.path;
+                quote_spanned!(span=> #private::FmtWith(#binding_value, #path))
+            } else if bonus_display {
                 quote_spanned!(span=> #binding_value.as_display())
             } else if bound == Trait::Pointer {
-                quote!(::thiserror::#private::Var(#binding_value))
+                quote!(#private::Var(#binding_value))
             } else {
                 binding_value.into_token_stream()
             };
+            // The real value stays in the generated code so that building with
+            // --cfg thiserror_unredact shows it, for debugging locally.
+            let wrapped_binding_value = if let Some(redact) = redact {
+                let replacement = match &redact.replacement {
+                    Some(replacement) => replacement.value(),
+                    None => "<redacted>".to_owned(),
+                };
+                quote_spanned! {span=>
+                    #private::Redacted(
+                        #wrapped_binding_value,
+                        #replacement,
+                        {
+                            #[allow(unexpected_cfgs)]
+                            let unredact = ::core::cfg!(thiserror_unredact);
+                            unredact
+                        },
+                    )
+                }
+            } else {
+                wrapped_binding_value
+            };
             bindings.push((formatvar.to_local(), wrapped_binding_value));
         }
 
@@ -157,12 +234,164 @@ impl Display<'_> {
         self.fmt = LitStr::new(&out, self.fmt.span());
         self.has_bonus_display = has_bonus_display;
         self.infinite_recursive = infinite_recursive;
+        self.source_in_display = source_in_display;
         self.implied_bounds = implied_bounds;
+        self.joined_fields = joined_fields;
         self.bindings = bindings;
         Ok(())
     }
 }
 
+impl Display<'_> {
+    // A format string that is not a literal can only be interpreted at
+    // runtime, where each of the remaining arguments is available as a
+    // positional `{}` or `{N}` placeholder. Arguments that are just a field
+    // are formatted with Display, which the field's type must implement.
+    fn expand_runtime_args(&mut self, fields: &[Field]) -> Result<()> {
+        let args = runtime_args.parse2(self.args.clone())?;
+        for arg in &args {
+            let ident = match syn::parse2::<Ident>(arg.clone()) {
+                Ok(ident) => ident,
+                Err(_) => continue,
+            };
+            let field = fields.iter().position(|field| match &field.member {
+                MemberUnraw::Named(name) => ident == name.to_local(),
+                MemberUnraw::Unnamed(index) => ident == format!("_{}", index.index),
+            });
+            if let Some(field) = field {
+                self.implied_bounds.insert((field, Trait::Display));
+            }
+        }
+        self.bindings = args
+            .into_iter()
+            .enumerate()
+            .map(|(i, arg)| (format_ident!("__arg{}", i), arg))
+            .collect();
+        Ok(())
+    }
+}
+
+impl Display<'_> {
+    // Checks the literal format string against the conventions for error
+    // messages: lowercase, no trailing period, no newlines, and no "error:"
+    // prefix. Each problem is recorded with a span pointing into the literal
+    // where the compiler supports it, and reported as a warning if the
+    // container has #[error(lint_messages)].
+    pub fn check_style(&mut self) {
+        let value = self.fmt.value();
+        let token = self.fmt.token();
+        let repr = token.to_string();
+        let (start, end) = match (repr.find('"'), repr.rfind('"')) {
+            (Some(start), Some(end)) if start < end => (start + 1, end),
+            _ => return,
+        };
+        let content = &repr[start..end];
+        let raw = repr.starts_with('r');
+        let subspan = |range: Range<usize>| token.subspan(range).unwrap_or_else(|| self.fmt.span());
+        let mut warnings = Vec::new();
+
+        if value
+            .get(..6)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("error:"))
+        {
+            let span = if content[..6].eq_ignore_ascii_case("error:") {
+                subspan(start..start + 6)
+            } else {
+                self.fmt.span()
+            };
+            warnings.push((
+                span,
+                "error messages should not be prefixed with \"error:\"",
+            ));
+        } else {
+            let word: String = value
+                .chars()
+                .take_while(|ch| ch.is_alphanumeric())
+                .collect();
+            let mut chars = word.chars();
+            if chars.next().is_some_and(char::is_uppercase) && chars.any(char::is_lowercase) {
+                let span = if content.starts_with(&word) {
+                    subspan(start..start + word.len())
+                } else {
+                    self.fmt.span()
+                };
+                warnings.push((span, "error messages should start with a lowercase letter"));
+            }
+        }
+
+        // A trailing newline is reported on its own below, and should not hide
+        // a period in front of it.
+        let trimmed = value.trim_end_matches('\n');
+        if trimmed.ends_with('.') && !trimmed.ends_with("..") {
+            let mut period_end = Some(end);
+            for _ in trimmed.len()..value.len() {
+                period_end = period_end.and_then(|end| {
+                    if content[..end - start].ends_with('\n') {
+                        Some(end - 1)
+                    } else if !raw && content[..end - start].ends_with("\\n") {
+                        Some(end - 2)
+                    } else {
+                        None
+                    }
+                });
+            }
+            let span = match period_end {
+                Some(end) if content[..end - start].ends_with('.') => subspan(end - 1..end),
+                _ => self.fmt.span(),
+            };
+            warnings.push((span, "error messages should not end with a period"));
+        }
+
+        let mut chars = content.char_indices();
+        while let Some((i, ch)) = chars.next() {
+            let len = match ch {
+                '\n' => 1,
+                '\\' if !raw => match chars.next() {
+                    Some((_, 'n')) => 2,
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            warnings.push((
+                subspan(start + i..start + i + len),
+                "error messages should not contain newlines",
+            ));
+            break;
+        }
+
+        self.style_warnings = warnings;
+    }
+}
+
+fn runtime_args(input: ParseStream) -> Result<Vec<TokenStream>> {
+    let mut syn_full = None;
+    let mut args = Vec::new();
+
+    while !input.is_empty() {
+        input.parse::<Token![,]>()?;
+        if input.is_empty() {
+            break;
+        }
+
+        if input.peek(Ident::peek_any) && input.peek2(Token![=]) && !input.peek2(Token![==]) {
+            return Err(input.error(
+                "named arguments are not supported when the format string is not a string literal",
+            ));
+        }
+
+        let begin = input.fork();
+        let ahead = input.fork();
+        if *syn_full.get_or_insert_with(is_syn_full) && ahead.parse::<Expr>().is_ok() {
+            input.advance_to(&ahead);
+        } else {
+            scan_expr(input)?;
+        }
+        args.push(between(&begin, input));
+    }
+
+    Ok(args)
+}
+
 struct FmtArguments {
     named: BTreeSet<IdentUnraw>,
     first_unnamed: Option<TokenStream>,
@@ -283,6 +512,19 @@ fn take_int<'a>(read: &mut &'a str) -> &'a str {
     int
 }
 
+// Consumes `:join("separator")` up to the closing brace of the placeholder.
+fn take_join_separator(read: &mut &str) -> Option<String> {
+    let rest = read.strip_prefix(":join(\"")?;
+    let end = rest.find('"')?;
+    let separator = rest[..end].to_owned();
+    let rest = rest[end + 1..].strip_prefix(')')?;
+    if !rest.starts_with('}') {
+        return None;
+    }
+    *read = rest;
+    Some(separator)
+}
+
 fn take_ident<'a>(read: &mut &'a str) -> &'a str {
     let mut ident_len = 0;
     for ch in read.chars() {
@@ -3,7 +3,10 @@ use quote::ToTokens;
 use std::collections::btree_map::Entry;
 use std::collections::{BTreeMap as Map, BTreeSet as Set};
 use syn::punctuated::Punctuated;
-use syn::{parse_quote, GenericArgument, Generics, Ident, PathArguments, Token, Type, WhereClause};
+use syn::{
+    parse_quote, GenericArgument, Generics, Ident, PathArguments, Token, Type, WhereClause,
+    WherePredicate,
+};
 
 pub struct ParamsInScope<'a> {
     names: Set<&'a Ident>,
@@ -48,6 +51,7 @@ fn crawl(in_scope: &ParamsInScope, ty: &Type, found: &mut bool) {
 pub struct InferredBounds {
     bounds: Map<String, (Set<String>, Punctuated<TokenStream, Token![+]>)>,
     order: Vec<TokenStream>,
+    explicit: Vec<WherePredicate>,
 }
 
 impl InferredBounds {
@@ -55,6 +59,7 @@ impl InferredBounds {
         InferredBounds {
             bounds: Map::new(),
             order: Vec::new(),
+            explicit: Vec::new(),
         }
     }
 
@@ -71,6 +76,15 @@ impl InferredBounds {
         }
     }
 
+    // Predicates written out by the user in #[error(bound = "...")]. These are
+    // added as is, alongside whatever bounds were inferred.
+    pub fn insert_explicit<'a>(
+        &mut self,
+        predicates: impl IntoIterator<Item = &'a WherePredicate>,
+    ) {
+        self.explicit.extend(predicates.into_iter().cloned());
+    }
+
     pub fn augment_where_clause(&self, generics: &Generics) -> WhereClause {
         let mut generics = generics.clone();
         let where_clause = generics.make_where_clause();
@@ -78,6 +92,9 @@ impl InferredBounds {
             let (_set, bounds) = &self.bounds[&ty.to_string()];
             where_clause.predicates.push(parse_quote!(#ty: #bounds));
         }
+        where_clause
+            .predicates
+            .extend(self.explicit.iter().cloned());
         generics.where_clause.unwrap()
     }
 }
@@ -33,20 +33,35 @@ mod valid;
 
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
-use quote::{ToTokens, TokenStreamExt as _};
-use syn::{parse_macro_input, DeriveInput};
+use quote::{quote, ToTokens, TokenStreamExt as _};
+use syn::{parse_macro_input, DeriveInput, Path};
 
-#[proc_macro_derive(Error, attributes(backtrace, error, from, source))]
+#[proc_macro_derive(Error, attributes(backtrace, error, from, location, provide, source))]
 pub fn derive_error(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     expand::derive(&input).into()
 }
 
-#[allow(non_camel_case_types)]
-struct private;
+#[proc_macro_derive(Display, attributes(display))]
+pub fn derive_display(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::derive_display(&input).into()
+}
+
+// Path to thiserror's private module, rooted at #[error(crate = ...)] if the
+// type being expanded has one.
+#[derive(Copy, Clone)]
+struct Private<'a> {
+    krate: Option<&'a Path>,
+}
 
-impl ToTokens for private {
+impl ToTokens for Private<'_> {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match self.krate {
+            Some(path) => path.to_tokens(tokens),
+            None => tokens.extend(quote!(::thiserror)),
+        }
+        tokens.extend(quote!(::));
         tokens.append(Ident::new(
             concat!("__private", env!("CARGO_PKG_VERSION_PATCH")),
             Span::call_site(),
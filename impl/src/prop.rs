@@ -1,7 +1,11 @@
 use crate::ast::{Enum, Field, Struct, Variant};
+use crate::attr::{Attrs, BoundImpl};
 use crate::unraw::MemberUnraw;
 use proc_macro2::Span;
-use syn::Type;
+use quote::ToTokens as _;
+use std::iter;
+use syn::ext::IdentExt as _;
+use syn::{GenericArgument, PathArguments, Type, WherePredicate};
 
 impl Struct<'_> {
     pub(crate) fn from_field(&self) -> Option<&Field> {
@@ -20,6 +24,22 @@ impl Struct<'_> {
         let backtrace_field = self.backtrace_field()?;
         distinct_backtrace_field(backtrace_field, self.from_field())
     }
+
+    pub(crate) fn location_field(&self) -> Option<&Field> {
+        location_field(&self.fields)
+    }
+
+    pub(crate) fn has_provide(&self) -> bool {
+        has_provide(&self.fields)
+    }
+
+    pub(crate) fn explicit_bounds(
+        &self,
+        which: BoundImpl,
+    ) -> impl Iterator<Item = &WherePredicate> {
+        let fields = self.fields.iter().map(|field| &field.attrs);
+        explicit_bounds(iter::once(&self.attrs).chain(fields), which)
+    }
 }
 
 impl Enum<'_> {
@@ -35,8 +55,42 @@ impl Enum<'_> {
             .any(|variant| variant.backtrace_field().is_some())
     }
 
+    pub(crate) fn has_location(&self) -> bool {
+        self.variants
+            .iter()
+            .any(|variant| variant.location_field().is_some())
+    }
+
+    pub(crate) fn has_provide(&self) -> bool {
+        self.variants.iter().any(Variant::has_provide)
+    }
+
+    // Variants with a #[from] field, grouped by the type they convert from in
+    // order of first appearance. Each group becomes a single From impl. Types
+    // are compared by their tokens because a proc macro cannot resolve paths,
+    // so `io::Error` and `std::io::Error` end up in different groups.
+    pub(crate) fn from_groups(&self) -> Vec<Vec<(&Variant, &Field, &Type)>> {
+        let mut groups: Vec<(String, Vec<_>)> = Vec::new();
+        for variant in &self.variants {
+            let from_field = match variant.from_field() {
+                Some(from_field) => from_field,
+                None => continue,
+            };
+            for from in from_field.from_types() {
+                let key = from.to_token_stream().to_string();
+                let entry = (variant, from_field, from);
+                match groups.iter_mut().find(|(existing, _)| *existing == key) {
+                    Some((_, group)) => group.push(entry),
+                    None => groups.push((key, vec![entry])),
+                }
+            }
+        }
+        groups.into_iter().map(|(_, group)| group).collect()
+    }
+
     pub(crate) fn has_display(&self) -> bool {
         self.attrs.display.is_some()
+            || self.attrs.doc.is_some()
             || self.attrs.transparent.is_some()
             || self.attrs.fmt.is_some()
             || self
@@ -48,6 +102,23 @@ impl Enum<'_> {
                 .iter()
                 .all(|variant| variant.attrs.transparent.is_some())
     }
+
+    pub(crate) fn has_code(&self) -> bool {
+        self.variants
+            .iter()
+            .any(|variant| variant.attrs.code.is_some())
+    }
+
+    pub(crate) fn explicit_bounds(
+        &self,
+        which: BoundImpl,
+    ) -> impl Iterator<Item = &WherePredicate> {
+        let variants = self.variants.iter().flat_map(|variant| {
+            let fields = variant.fields.iter().map(|field| &field.attrs);
+            iter::once(&variant.attrs).chain(fields)
+        });
+        explicit_bounds(iter::once(&self.attrs).chain(variants), which)
+    }
 }
 
 impl Variant<'_> {
@@ -67,6 +138,48 @@ impl Variant<'_> {
         let backtrace_field = self.backtrace_field()?;
         distinct_backtrace_field(backtrace_field, self.from_field())
     }
+
+    pub(crate) fn location_field(&self) -> Option<&Field> {
+        location_field(&self.fields)
+    }
+
+    pub(crate) fn has_provide(&self) -> bool {
+        has_provide(&self.fields)
+    }
+
+    pub(crate) fn explicit_bounds(
+        &self,
+        which: BoundImpl,
+    ) -> impl Iterator<Item = &WherePredicate> {
+        let fields = self.fields.iter().map(|field| &field.attrs);
+        explicit_bounds(iter::once(&self.attrs).chain(fields), which)
+    }
+
+    // The variant name in snake case, as used by the functions generated by
+    // #[error(constructors)] and #[error(accessors)].
+    pub(crate) fn snake_case_name(&self) -> String {
+        let name = self.ident.unraw().to_string();
+        let chars: Vec<char> = name.chars().collect();
+        let mut snake = String::new();
+        for (i, &ch) in chars.iter().enumerate() {
+            if ch.is_uppercase() {
+                let prev = i.checked_sub(1).map(|i| chars[i]);
+                let next = chars.get(i + 1);
+                let word_start = prev.is_some_and(|prev| {
+                    prev.is_lowercase()
+                        || prev.is_ascii_digit()
+                        || prev.is_uppercase() && next.is_some_and(|next| next.is_lowercase())
+                });
+                if word_start {
+                    snake.push('_');
+                }
+                snake.extend(ch.to_lowercase());
+            } else {
+                snake.push(ch);
+            }
+        }
+        snake
+    }
 }
 
 impl Field<'_> {
@@ -74,6 +187,29 @@ impl Field<'_> {
         type_is_backtrace(self.ty)
     }
 
+    pub(crate) fn is_location(&self) -> bool {
+        type_is_location(self.ty)
+    }
+
+    // The types listed in #[error(from(A, B))], or else the #[from] field's own type
+    // without Option.
+    pub(crate) fn from_types(&self) -> Vec<&Type> {
+        match &self.attrs.from {
+            Some(from) if !from.types.is_empty() => from.types.iter().collect(),
+            Some(_) => vec![type_parameter_of_option(self.ty).unwrap_or(self.ty)],
+            None => Vec::new(),
+        }
+    }
+
+    // The E in a #[from(boxed)] field of type Box<E> or Arc<E>.
+    pub(crate) fn from_pointee(&self) -> Option<&Type> {
+        if !self.attrs.from.as_ref()?.boxed {
+            return None;
+        }
+        let ty = type_parameter_of_option(self.ty).unwrap_or(self.ty);
+        type_parameter_of_pointer(ty)
+    }
+
     pub(crate) fn source_span(&self) -> Span {
         if let Some(source_attr) = &self.attrs.source {
             source_attr.span
@@ -94,7 +230,7 @@ fn from_field<'a, 'b>(fields: &'a [Field<'b>]) -> Option<&'a Field<'b>> {
     None
 }
 
-fn source_field<'a, 'b>(fields: &'a [Field<'b>]) -> Option<&'a Field<'b>> {
+pub(crate) fn source_field<'a, 'b>(fields: &'a [Field<'b>]) -> Option<&'a Field<'b>> {
     for field in fields {
         if field.attrs.from.is_some() || field.attrs.source.is_some() {
             return Some(field);
@@ -123,6 +259,24 @@ fn backtrace_field<'a, 'b>(fields: &'a [Field<'b>]) -> Option<&'a Field<'b>> {
     None
 }
 
+fn location_field<'a, 'b>(fields: &'a [Field<'b>]) -> Option<&'a Field<'b>> {
+    for field in fields {
+        if field.attrs.location.is_some() {
+            return Some(field);
+        }
+    }
+    for field in fields {
+        if field.is_location() {
+            return Some(field);
+        }
+    }
+    None
+}
+
+fn has_provide(fields: &[Field]) -> bool {
+    fields.iter().any(|field| field.attrs.provide.is_some())
+}
+
 // The #[backtrace] field, if it is not the same as the #[from] field.
 fn distinct_backtrace_field<'a, 'b>(
     backtrace_field: &'a Field<'b>,
@@ -144,3 +298,102 @@ fn type_is_backtrace(ty: &Type) -> bool {
     let last = path.segments.last().unwrap();
     last.ident == "Backtrace" && last.arguments.is_empty()
 }
+
+// &'static Location<'static>, as returned by Location::caller(). The path may
+// be written as `Location`, `panic::Location`, or in full from core or std;
+// any other type needs #[location].
+fn type_is_location(ty: &Type) -> bool {
+    let reference = match ty {
+        Type::Reference(ty) => ty,
+        _ => return false,
+    };
+
+    if reference.mutability.is_some()
+        || !reference
+            .lifetime
+            .as_ref()
+            .is_some_and(|lifetime| lifetime.ident == "static")
+    {
+        return false;
+    }
+
+    let path = match &*reference.elem {
+        Type::Path(ty) if ty.qself.is_none() => &ty.path,
+        _ => return false,
+    };
+
+    let mut segments = path.segments.iter().rev();
+    let last = segments.next().unwrap();
+    if last.ident != "Location" {
+        return false;
+    }
+    let lifetime_is_static = match &last.arguments {
+        PathArguments::AngleBracketed(bracketed) if bracketed.args.len() == 1 => {
+            match &bracketed.args[0] {
+                GenericArgument::Lifetime(lifetime) => lifetime.ident == "static",
+                _ => false,
+            }
+        }
+        _ => false,
+    };
+    if !lifetime_is_static {
+        return false;
+    }
+
+    let prefix: Vec<_> = segments.rev().collect();
+    match prefix.as_slice() {
+        [] => path.leading_colon.is_none(),
+        [panic] => panic.ident == "panic" && path.leading_colon.is_none(),
+        [krate, panic] => (krate.ident == "core" || krate.ident == "std") && panic.ident == "panic",
+        _ => false,
+    }
+}
+
+// The E in a field of type Box<E> or Arc<E>, for which #[from(boxed)]
+// generates a From<E> impl in addition to From<Box<E>>. Unsized E like a trait
+// object can not be passed by value to from(), so those are not accepted.
+fn type_parameter_of_pointer(ty: &Type) -> Option<&Type> {
+    let arg = type_parameter_of(ty, "Box").or_else(|| type_parameter_of(ty, "Arc"))?;
+    match arg {
+        Type::TraitObject(_) | Type::Slice(_) => None,
+        Type::Path(arg) if arg.qself.is_none() && arg.path.is_ident("str") => None,
+        _ => Some(arg),
+    }
+}
+
+pub(crate) fn type_parameter_of_option(ty: &Type) -> Option<&Type> {
+    type_parameter_of(ty, "Option")
+}
+
+pub(crate) fn type_parameter_of<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let path = match ty {
+        Type::Path(ty) => &ty.path,
+        _ => return None,
+    };
+
+    let last = path.segments.last().unwrap();
+    if last.ident != wrapper {
+        return None;
+    }
+
+    let bracketed = match &last.arguments {
+        PathArguments::AngleBracketed(bracketed) => bracketed,
+        _ => return None,
+    };
+
+    if bracketed.args.len() != 1 {
+        return None;
+    }
+
+    match &bracketed.args[0] {
+        GenericArgument::Type(arg) => Some(arg),
+        _ => None,
+    }
+}
+
+fn explicit_bounds<'a>(
+    attrs: impl Iterator<Item = &'a Attrs<'a>>,
+    which: BoundImpl,
+) -> impl Iterator<Item = &'a WherePredicate> {
+    attrs.flat_map(move |attrs| attrs.bound(which).unwrap_or_default())
+}
@@ -1,6 +1,8 @@
 use crate::ast::{Enum, Field, Input, Struct, Variant};
 use crate::attr::Attrs;
-use syn::{Error, GenericArgument, PathArguments, Result, Type};
+use proc_macro2::Span;
+use std::collections::BTreeMap as Map;
+use syn::{Error, GenericArgument, PathArguments, Result, Type, TypeParamBound};
 
 impl Input<'_> {
     pub(crate) fn validate(&self) -> Result<()> {
@@ -9,6 +11,13 @@ impl Input<'_> {
             Input::Enum(input) => input.validate(),
         }
     }
+
+    pub(crate) fn validate_display(&self) -> Result<()> {
+        match self {
+            Input::Struct(input) => input.validate_display(),
+            Input::Enum(input) => input.validate_display(),
+        }
+    }
 }
 
 impl Struct<'_> {
@@ -34,20 +43,226 @@ impl Struct<'_> {
                 "#[error(fmt = ...)] is only supported in enums; for a struct, handwrite your own Display impl",
             ));
         }
+        if let Some(kind) = &self.attrs.kind {
+            return Err(Error::new_spanned(
+                kind.original,
+                "#[error(kind = ...)] is only supported in enums",
+            ));
+        }
+        if let Some(context) = self.attrs.context {
+            return Err(Error::new_spanned(
+                context,
+                "#[error(context)] is only supported in enums",
+            ));
+        }
+        if let Some(constructors) = self.attrs.constructors {
+            return Err(Error::new_spanned(
+                constructors,
+                "#[error(constructors)] is only supported in enums",
+            ));
+        }
+        if let Some(try_from) = self.attrs.try_from {
+            return Err(Error::new_spanned(
+                try_from,
+                "#[error(try_from)] is only supported in enums",
+            ));
+        }
+        if let Some(accessors) = self.attrs.accessors {
+            return Err(Error::new_spanned(
+                accessors,
+                "#[error(accessors)] is only supported in enums",
+            ));
+        }
+        if let (Some(exit_code), None) = (&self.attrs.exit_code, self.attrs.termination) {
+            return Err(Error::new_spanned(
+                exit_code.original,
+                "#[error(exit_code = ...)] requires #[error(termination)] on the struct",
+            ));
+        }
+        if let Some(from) = self.fields.iter().find_map(|f| f.attrs.from.as_ref()) {
+            if from.when.is_some() {
+                return Err(Error::new_spanned(
+                    from.original,
+                    "#[from(when = ...)] is only supported in enums",
+                ));
+            }
+        }
         check_field_attrs(&self.fields)?;
         for field in &self.fields {
             field.validate()?;
         }
         Ok(())
     }
+
+    fn validate_display(&self) -> Result<()> {
+        check_display_non_field_attrs(&self.attrs)?;
+        if let Some(fmt) = &self.attrs.fmt {
+            return Err(Error::new_spanned(
+                fmt.original,
+                "#[display(fmt = ...)] is only supported in enums; for a struct, handwrite your own Display impl",
+            ));
+        }
+        if let Some(transparent) = self.attrs.transparent {
+            if self.fields.len() != 1 {
+                return Err(Error::new_spanned(
+                    transparent.original,
+                    "#[display(transparent)] requires exactly one field",
+                ));
+            }
+        } else if self.attrs.display.is_none() {
+            return Err(Error::new(
+                Span::call_site(),
+                "missing #[display(\"...\")] attribute",
+            ));
+        }
+        for field in &self.fields {
+            field.validate_display()?;
+        }
+        Ok(())
+    }
 }
 
 impl Enum<'_> {
     fn validate(&self) -> Result<()> {
         check_non_field_attrs(&self.attrs)?;
+        if let Some(code) = &self.attrs.code {
+            return Err(Error::new_spanned(
+                code.original,
+                "not expected here; the #[error(code = ...)] attribute belongs on a specific variant",
+            ));
+        }
+        if let Some(kind) = &self.attrs.kind {
+            if kind.path.get_ident().is_none() {
+                return Err(Error::new_spanned(
+                    &kind.path,
+                    "expected an identifier to name the generated kind enum",
+                ));
+            }
+        }
+        if let (Some(exit_code), None) = (&self.attrs.exit_code, self.attrs.termination) {
+            return Err(Error::new_spanned(
+                exit_code.original,
+                "#[error(exit_code = ...)] requires #[error(termination)] on the enum",
+            ));
+        }
+        if let Some(context) = self.attrs.context {
+            if !self.generics.params.is_empty() {
+                return Err(Error::new_spanned(
+                    context,
+                    "#[error(context)] is not supported on generic enums",
+                ));
+            }
+        }
         let has_display = self.has_display();
+        let has_code = self.has_code();
+        let mut codes = Map::new();
+        let mut methods = Map::new();
         for variant in &self.variants {
             variant.validate()?;
+            if let Some(krate) = variant.attrs.krate {
+                return Err(Error::new_spanned(
+                    krate,
+                    "not expected here; the #[error(crate = ...)] attribute belongs on top of the enum",
+                ));
+            }
+            if let Some(termination) = variant.attrs.termination {
+                return Err(Error::new_spanned(
+                    termination,
+                    "not expected here; the #[error(termination)] attribute belongs on top of the enum",
+                ));
+            }
+            if let Some(debug) = variant.attrs.debug {
+                return Err(Error::new_spanned(
+                    debug,
+                    "not expected here; the #[error(debug = ...)] attribute belongs on top of the enum",
+                ));
+            }
+            if let Some(context) = variant.attrs.context {
+                return Err(Error::new_spanned(
+                    context,
+                    "not expected here; the #[error(context)] attribute belongs on top of the enum",
+                ));
+            }
+            if self.attrs.try_from.is_some() && variant.attrs.transparent.is_none() {
+                if let Some(source_field) = variant.source_field() {
+                    if contains_unsendable_trait_object(source_field.ty) {
+                        return Err(Error::new_spanned(
+                            source_field.ty,
+                            "into_source() needs a source that converts into Box<dyn Error + Send + Sync>; add `+ Send + Sync` to the trait object",
+                        ));
+                    }
+                }
+            }
+            if let Some(try_from) = variant.attrs.try_from {
+                if variant.from_field().is_none() {
+                    return Err(Error::new_spanned(
+                        try_from,
+                        "#[error(try_from)] on a variant requires a #[from] field",
+                    ));
+                }
+            }
+            if let Some(constructors) = variant.attrs.constructors {
+                return Err(Error::new_spanned(
+                    constructors,
+                    "not expected here; the #[error(constructors)] attribute belongs on top of the enum",
+                ));
+            }
+            if let Some(accessors) = variant.attrs.accessors {
+                return Err(Error::new_spanned(
+                    accessors,
+                    "not expected here; the #[error(accessors)] attribute belongs on top of the enum",
+                ));
+            }
+            if let Some(lint_messages) = variant.attrs.lint_messages {
+                return Err(Error::new_spanned(
+                    lint_messages,
+                    "not expected here; the #[error(lint_messages)] attribute belongs on top of the enum",
+                ));
+            }
+            let name = variant.snake_case_name();
+            let mut generated = Vec::new();
+            if self.attrs.constructors.is_some() {
+                if ["self", "super", "crate"].contains(&name.as_str())
+                    || name == "code" && has_code
+                    || name == "kind" && self.attrs.kind.is_some()
+                    || name == "variant_name" && self.attrs.accessors.is_some()
+                    || name == "into_source" && self.attrs.try_from.is_some()
+                {
+                    return Err(Error::new_spanned(
+                        &variant.ident,
+                        format!(
+                            "#[error(constructors)] cannot generate a function named `{}`",
+                            name
+                        ),
+                    ));
+                }
+                generated.push(("constructor", name.clone()));
+            }
+            if self.attrs.accessors.is_some() {
+                generated.push(("method", format!("is_{}", name)));
+                if !variant.fields.is_empty() {
+                    generated.push(("method", format!("as_{}", name)));
+                }
+            }
+            for (what, name) in generated {
+                if let Some(first) = methods.insert(name.clone(), &variant.ident) {
+                    return Err(Error::new_spanned(
+                        &variant.ident,
+                        format!(
+                            "{} `{}` would be generated for both `{}` and `{}`",
+                            what, name, first, variant.ident,
+                        ),
+                    ));
+                }
+            }
+            if self.attrs.termination.is_none() {
+                if let Some(exit_code) = &variant.attrs.exit_code {
+                    return Err(Error::new_spanned(
+                        exit_code.original,
+                        "#[error(exit_code = ...)] requires #[error(termination)] on the enum",
+                    ));
+                }
+            }
             if has_display
                 && variant.attrs.display.is_none()
                 && variant.attrs.transparent.is_none()
@@ -58,6 +273,106 @@ impl Enum<'_> {
                     "missing #[error(\"...\")] display attribute",
                 ));
             }
+            if let Some(kind) = &variant.attrs.kind {
+                if self.attrs.kind.is_none() {
+                    return Err(Error::new_spanned(
+                        kind.original,
+                        "#[error(kind = ...)] on a variant requires #[error(kind = ...)] on the enum",
+                    ));
+                }
+                if variant.attrs.transparent.is_none() {
+                    return Err(Error::new_spanned(
+                        kind.original,
+                        "#[error(kind = ...)] on a variant is only supported together with #[error(transparent)]",
+                    ));
+                }
+            }
+            if has_code {
+                let code = match &variant.attrs.code {
+                    Some(code) => code,
+                    None => {
+                        return Err(Error::new_spanned(
+                            variant.original,
+                            "missing #[error(code = \"...\")] attribute",
+                        ));
+                    }
+                };
+                if let Some(first) = codes.insert(code.lit.value(), &variant.ident) {
+                    return Err(Error::new_spanned(
+                        &code.lit,
+                        format!(
+                            "duplicate error code {:?}, already used by variant `{}`",
+                            code.lit.value(),
+                            first,
+                        ),
+                    ));
+                }
+            }
+        }
+        for group in self.from_groups() {
+            let is_conditional = |field: &Field| field.attrs.from.as_ref().unwrap().when.is_some();
+            let conditional = match group.iter().find(|(_, field, _)| is_conditional(field)) {
+                Some((_, field, _)) => field,
+                // Unconditional duplicates are reported by rustc as
+                // conflicting impls.
+                None => continue,
+            };
+            let mut fallbacks = group.iter().filter(|(_, field, _)| !is_conditional(field));
+            match (fallbacks.next(), fallbacks.next()) {
+                (None, _) => {
+                    return Err(Error::new_spanned(
+                        conditional.attrs.from.as_ref().unwrap().original,
+                        "#[from(when = ...)] requires another variant with an unconditional #[from] of the same type, written the same way",
+                    ));
+                }
+                (Some(_), Some((_, second, _))) => {
+                    return Err(Error::new_spanned(
+                        second.attrs.from.as_ref().unwrap().original,
+                        "only one variant may have an unconditional #[from] of a type that is also routed with #[from(when = ...)]",
+                    ));
+                }
+                (Some((_, fallback, _)), None) => {
+                    // The conditions take the field type by reference, but the
+                    // extra From impl of a boxed fallback would hand them the
+                    // unboxed value.
+                    if fallback.attrs.from.as_ref().unwrap().boxed {
+                        return Err(Error::new_spanned(
+                            conditional.attrs.from.as_ref().unwrap().original,
+                            "#[from(when = ...)] cannot be combined with a #[from(boxed)] variant of the same type",
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_display(&self) -> Result<()> {
+        check_display_non_field_attrs(&self.attrs)?;
+        for variant in &self.variants {
+            check_display_non_field_attrs(&variant.attrs)?;
+            if let Some(krate) = variant.attrs.krate {
+                return Err(Error::new_spanned(
+                    krate,
+                    "not expected here; the #[display(crate = ...)] attribute belongs on top of the enum",
+                ));
+            }
+            if variant.attrs.transparent.is_some() {
+                if variant.fields.len() != 1 {
+                    return Err(Error::new_spanned(
+                        variant.original,
+                        "#[display(transparent)] requires exactly one field",
+                    ));
+                }
+            } else if variant.attrs.display.is_none() && variant.attrs.fmt.is_none() {
+                return Err(Error::new_spanned(
+                    variant.original,
+                    "missing #[display(\"...\")] attribute",
+                ));
+            }
+            for field in &variant.fields {
+                field.validate_display()?;
+            }
         }
         Ok(())
     }
@@ -94,6 +409,32 @@ impl Field<'_> {
             Some(display.original)
         } else if let Some(fmt) = &self.attrs.fmt {
             Some(fmt.original)
+        } else if let Some(code) = &self.attrs.code {
+            Some(code.original)
+        } else if let Some(kind) = &self.attrs.kind {
+            Some(kind.original)
+        } else if let Some(doc) = self.attrs.doc {
+            Some(doc)
+        } else if let Some(exit_code) = &self.attrs.exit_code {
+            Some(exit_code.original)
+        } else if let Some(termination) = self.attrs.termination {
+            Some(termination)
+        } else if let Some(krate) = self.attrs.krate {
+            Some(krate)
+        } else if let Some(debug) = self.attrs.debug {
+            Some(debug)
+        } else if let Some(context) = self.attrs.context {
+            Some(context)
+        } else if let Some(constructors) = self.attrs.constructors {
+            Some(constructors)
+        } else if let Some(try_from) = self.attrs.try_from {
+            Some(try_from)
+        } else if let Some(accessors) = self.attrs.accessors {
+            Some(accessors)
+        } else if let Some(allow_source_in_display) = self.attrs.allow_source_in_display {
+            Some(allow_source_in_display)
+        } else if let Some(lint_messages) = self.attrs.lint_messages {
+            Some(lint_messages)
         } else {
             None
         } {
@@ -102,8 +443,42 @@ impl Field<'_> {
                 "not expected here; the #[error(...)] attribute belongs on top of a struct or an enum variant",
             ));
         }
+        if let (Some(redact), Some(_)) = (&self.attrs.redact, &self.attrs.fmt_with) {
+            return Err(Error::new_spanned(
+                redact.original,
+                "cannot have both #[error(redact)] and #[error(fmt_with = ...)]",
+            ));
+        }
         Ok(())
     }
+
+    fn validate_display(&self) -> Result<()> {
+        if let Some(unexpected_display_attr) = if let Some(display) = &self.attrs.display {
+            Some(display.original)
+        } else if let Some(fmt) = &self.attrs.fmt {
+            Some(fmt.original)
+        } else if let Some(transparent) = self.attrs.transparent {
+            Some(transparent.original)
+        } else if let Some(doc) = self.attrs.doc {
+            Some(doc)
+        } else if let Some(krate) = self.attrs.krate {
+            Some(krate)
+        } else {
+            None
+        } {
+            return Err(Error::new_spanned(
+                unexpected_display_attr,
+                "not expected here; the #[display(...)] attribute belongs on top of a struct or an enum variant",
+            ));
+        }
+        if let (Some(redact), Some(_)) = (&self.attrs.redact, &self.attrs.fmt_with) {
+            return Err(Error::new_spanned(
+                redact.original,
+                "cannot have both #[display(redact)] and #[display(fmt_with = ...)]",
+            ));
+        }
+        check_display_attrs(&self.attrs)
+    }
 }
 
 fn check_non_field_attrs(attrs: &Attrs) -> Result<()> {
@@ -125,6 +500,36 @@ fn check_non_field_attrs(attrs: &Attrs) -> Result<()> {
             "not expected here; the #[backtrace] attribute belongs on a specific field",
         ));
     }
+    if let Some(location) = &attrs.location {
+        return Err(Error::new_spanned(
+            location,
+            "not expected here; the #[location] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(default) = &attrs.default {
+        return Err(Error::new_spanned(
+            default.original,
+            "not expected here; the #[error(default)] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(provide) = &attrs.provide {
+        return Err(Error::new_spanned(
+            provide.original,
+            "not expected here; the #[provide] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(redact) = &attrs.redact {
+        return Err(Error::new_spanned(
+            redact.original,
+            "not expected here; the #[error(redact)] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(fmt_with) = &attrs.fmt_with {
+        return Err(Error::new_spanned(
+            fmt_with.original,
+            "not expected here; the #[error(fmt_with = ...)] attribute belongs on a specific field",
+        ));
+    }
     if attrs.transparent.is_some() {
         if let Some(display) = &attrs.display {
             return Err(Error::new_spanned(
@@ -148,19 +553,100 @@ fn check_non_field_attrs(attrs: &Attrs) -> Result<()> {
     Ok(())
 }
 
+fn check_display_non_field_attrs(attrs: &Attrs) -> Result<()> {
+    if let Some(redact) = &attrs.redact {
+        return Err(Error::new_spanned(
+            redact.original,
+            "not expected here; the #[display(redact)] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(fmt_with) = &attrs.fmt_with {
+        return Err(Error::new_spanned(
+            fmt_with.original,
+            "not expected here; the #[display(fmt_with = ...)] attribute belongs on a specific field",
+        ));
+    }
+    check_display_attrs(attrs)
+}
+
+// The #[error(...)] arguments which have no meaning for a Display impl.
+fn check_display_attrs(attrs: &Attrs) -> Result<()> {
+    if let Some(unsupported) = if let Some(code) = &attrs.code {
+        Some(code.original)
+    } else if let Some(kind) = &attrs.kind {
+        Some(kind.original)
+    } else if let Some(exit_code) = &attrs.exit_code {
+        Some(exit_code.original)
+    } else if let Some(termination) = attrs.termination {
+        Some(termination)
+    } else if let Some(default) = &attrs.default {
+        Some(default.original)
+    } else if let Some(debug) = attrs.debug {
+        Some(debug)
+    } else if let Some(from) = &attrs.from {
+        Some(from.original)
+    } else if let Some(context) = attrs.context {
+        Some(context)
+    } else if let Some(constructors) = attrs.constructors {
+        Some(constructors)
+    } else if let Some(try_from) = attrs.try_from {
+        Some(try_from)
+    } else if let Some(accessors) = attrs.accessors {
+        Some(accessors)
+    } else if let Some(allow_source_in_display) = attrs.allow_source_in_display {
+        Some(allow_source_in_display)
+    } else if let Some(lint_messages) = attrs.lint_messages {
+        Some(lint_messages)
+    } else {
+        None
+    } {
+        return Err(Error::new_spanned(
+            unsupported,
+            "not supported by derive(Display); this belongs in an #[error(...)] attribute",
+        ));
+    }
+    if attrs.transparent.is_some() {
+        if let Some(display) = &attrs.display {
+            return Err(Error::new_spanned(
+                display.original,
+                "cannot have both #[display(transparent)] and a format string",
+            ));
+        }
+        if let Some(fmt) = &attrs.fmt {
+            return Err(Error::new_spanned(
+                fmt.original,
+                "cannot have both #[display(transparent)] and #[display(fmt = ...)]",
+            ));
+        }
+    } else if let (Some(display), Some(_)) = (&attrs.display, &attrs.fmt) {
+        return Err(Error::new_spanned(
+            display.original,
+            "cannot have both #[display(fmt = ...)] and a format string",
+        ));
+    }
+    Ok(())
+}
+
 fn check_field_attrs(fields: &[Field]) -> Result<()> {
     let mut from_field = None;
     let mut source_field = None;
     let mut backtrace_field = None;
     let mut has_backtrace = false;
+    let mut location_field = None;
     for field in fields {
-        if let Some(from) = field.attrs.from {
+        if let Some(from) = &field.attrs.from {
             if from_field.is_some() {
                 return Err(Error::new_spanned(
                     from.original,
                     "duplicate #[from] attribute",
                 ));
             }
+            if from.boxed && field.from_pointee().is_none() {
+                return Err(Error::new_spanned(
+                    from.original,
+                    "#[from(boxed)] requires a field of type Box<E> or Arc<E> where E is sized",
+                ));
+            }
             from_field = Some(field);
         }
         if let Some(source) = field.attrs.source {
@@ -182,6 +668,15 @@ fn check_field_attrs(fields: &[Field]) -> Result<()> {
             backtrace_field = Some(field);
             has_backtrace = true;
         }
+        if let Some(location) = field.attrs.location {
+            if location_field.is_some() {
+                return Err(Error::new_spanned(
+                    location,
+                    "duplicate #[location] attribute",
+                ));
+            }
+            location_field = Some(field);
+        }
         if let Some(transparent) = field.attrs.transparent {
             return Err(Error::new_spanned(
                 transparent.original,
@@ -190,10 +685,34 @@ fn check_field_attrs(fields: &[Field]) -> Result<()> {
         }
         has_backtrace |= field.is_backtrace();
     }
+    let mut default_fields = 0;
+    for field in fields {
+        if let Some(default) = &field.attrs.default {
+            if from_field.is_none() {
+                return Err(Error::new_spanned(
+                    default.original,
+                    "#[error(default)] is only supported alongside a #[from] field",
+                ));
+            }
+            if field.attrs.from.is_some()
+                || field.attrs.source.is_some()
+                || field.attrs.backtrace.is_some()
+                || field.is_backtrace()
+                || field.attrs.location.is_some()
+                || field.is_location()
+            {
+                return Err(Error::new_spanned(
+                    default.original,
+                    "#[error(default)] is not supported on the source, backtrace, or location field",
+                ));
+            }
+            default_fields += 1;
+        }
+    }
     if let (Some(from_field), Some(source_field)) = (from_field, source_field) {
         if from_field.member != source_field.member {
             return Err(Error::new_spanned(
-                from_field.attrs.from.unwrap().original,
+                from_field.attrs.from.as_ref().unwrap().original,
                 "#[from] is only supported on the source field, not any other field",
             ));
         }
@@ -202,11 +721,14 @@ fn check_field_attrs(fields: &[Field]) -> Result<()> {
         let max_expected_fields = match backtrace_field {
             Some(backtrace_field) => 1 + (from_field.member != backtrace_field.member) as usize,
             None => 1 + has_backtrace as usize,
-        };
+        } + location_field
+            .or_else(|| fields.iter().find(|field| field.is_location()))
+            .is_some() as usize
+            + default_fields;
         if fields.len() > max_expected_fields {
             return Err(Error::new_spanned(
-                from_field.attrs.from.unwrap().original,
-                "deriving From requires no fields other than source and backtrace",
+                from_field.attrs.from.as_ref().unwrap().original,
+                "deriving From requires no fields other than source, backtrace, and location, except those with #[error(default)]",
             ));
         }
     }
@@ -246,3 +768,30 @@ fn contains_non_static_lifetime(ty: &Type) -> bool {
         _ => false, // maybe implement later if there are common other cases
     }
 }
+
+fn contains_unsendable_trait_object(ty: &Type) -> bool {
+    match ty {
+        Type::Path(ty) => {
+            let bracketed = match &ty.path.segments.last().unwrap().arguments {
+                PathArguments::AngleBracketed(bracketed) => bracketed,
+                _ => return false,
+            };
+            bracketed.args.iter().any(|arg| match arg {
+                GenericArgument::Type(ty) => contains_unsendable_trait_object(ty),
+                _ => false,
+            })
+        }
+        Type::TraitObject(ty) => {
+            let has_bound = |name: &str| {
+                ty.bounds.iter().any(|bound| match bound {
+                    TypeParamBound::Trait(bound) => {
+                        bound.path.segments.last().unwrap().ident == name
+                    }
+                    _ => false,
+                })
+            };
+            !(has_bound("Send") && has_bound("Sync"))
+        }
+        _ => false,
+    }
+}
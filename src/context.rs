@@ -0,0 +1,71 @@
+/// Builds an error out of a source error plus some context.
+///
+/// This is implemented by the context selectors that `#[error(context)]`
+/// generates, one for each variant that has a source. It is normally used
+/// through [`ResultExt::context`] rather than called directly.
+pub trait IntoError<S> {
+    /// The error type produced.
+    type Error;
+
+    /// Combines the context with the source error.
+    fn into_error(self, source: S) -> Self::Error;
+}
+
+/// Extension methods for attaching context to the error in a `Result`.
+///
+/// ```
+/// # use std::fs;
+/// # use std::io;
+/// # use std::path::PathBuf;
+/// use thiserror::{Error, ResultExt as _};
+///
+/// #[derive(Error, Debug)]
+/// #[error(context)]
+/// pub enum ConfigError {
+///     #[error("failed to read {path}")]
+///     ReadConfig { path: PathBuf, source: io::Error },
+/// }
+///
+/// fn read_config(path: PathBuf) -> Result<String, ConfigError> {
+///     fs::read_to_string(&path).context(ReadConfigCtx { path })
+/// }
+/// ```
+pub trait ResultExt<T, S> {
+    /// Converts the error, if any, into the error built by the given context
+    /// selector.
+    fn context<C>(self, context: C) -> Result<T, C::Error>
+    where
+        C: IntoError<S>;
+
+    /// Like [`context`][ResultExt::context], but only creates the context
+    /// selector if there is an error.
+    fn with_context<C, F>(self, context: F) -> Result<T, C::Error>
+    where
+        C: IntoError<S>,
+        F: FnOnce() -> C;
+}
+
+impl<T, S> ResultExt<T, S> for Result<T, S> {
+    #[track_caller]
+    fn context<C>(self, context: C) -> Result<T, C::Error>
+    where
+        C: IntoError<S>,
+    {
+        match self {
+            Ok(value) => Ok(value),
+            Err(source) => Err(context.into_error(source)),
+        }
+    }
+
+    #[track_caller]
+    fn with_context<C, F>(self, context: F) -> Result<T, C::Error>
+    where
+        C: IntoError<S>,
+        F: FnOnce() -> C,
+    {
+        match self {
+            Ok(value) => Ok(value),
+            Err(source) => Err(context().into_error(source)),
+        }
+    }
+}
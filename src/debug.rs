@@ -0,0 +1,11 @@
+use crate::Report;
+use core::error::Error;
+use core::fmt::{self, Display};
+
+// Used by the Debug impl generated for #[error(debug = display)]. Renders the
+// error's message followed by its chain of sources, which is what ends up
+// printed when the error is returned from `main`.
+#[doc(hidden)]
+pub fn debug_as_display(error: &dyn Error, formatter: &mut fmt::Formatter) -> fmt::Result {
+    Display::fmt(&Report::new(error).pretty(true), formatter)
+}
@@ -0,0 +1,73 @@
+use core::fmt::{self, Debug, Display};
+
+// Used when the format string of an #[error(...)] attribute is not a string
+// literal, so rustc's format_args cannot parse it at compile time. Supports
+// `{}` and `{N}` placeholders referring to the positional arguments, and `{{`
+// and `}}` escapes. Any other placeholder is written out literally.
+#[doc(hidden)]
+pub fn write_runtime_fmt(
+    formatter: &mut fmt::Formatter,
+    fmt: &str,
+    args: &[&dyn Display],
+) -> fmt::Result {
+    let mut next_arg = 0;
+    let mut rest = fmt;
+    while let Some(brace) = rest.find(['{', '}']) {
+        let (literal, placeholder) = rest.split_at(brace);
+        formatter.write_str(literal)?;
+        if placeholder.starts_with("{{") || placeholder.starts_with("}}") {
+            formatter.write_str(&placeholder[..1])?;
+            rest = &placeholder[2..];
+            continue;
+        }
+        let end = placeholder
+            .find('}')
+            .filter(|_| placeholder.starts_with('{'));
+        let arg = end.and_then(|end| {
+            let index = match &placeholder[1..end] {
+                "" => {
+                    next_arg += 1;
+                    next_arg - 1
+                }
+                index => index.parse().ok()?,
+            };
+            Some((args.get(index)?, end))
+        });
+        match arg {
+            Some((arg, end)) => {
+                Display::fmt(*arg, formatter)?;
+                rest = &placeholder[end + 1..];
+            }
+            None => {
+                formatter.write_str(&placeholder[..1])?;
+                rest = &placeholder[1..];
+            }
+        }
+    }
+    formatter.write_str(rest)
+}
+
+// Stands in for a field marked #[error(fmt_with = path)] wherever the field is
+// interpolated into the message, and formats it by calling the function.
+#[doc(hidden)]
+pub struct FmtWith<'a, T: ?Sized, F>(pub &'a T, pub F);
+
+impl<T, F> Display for FmtWith<'_, T, F>
+where
+    T: ?Sized,
+    F: Fn(&T, &mut fmt::Formatter) -> fmt::Result,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        (self.1)(self.0, formatter)
+    }
+}
+
+impl<T, F> Debug for FmtWith<'_, T, F>
+where
+    T: ?Sized,
+    F: Fn(&T, &mut fmt::Formatter) -> fmt::Result,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        (self.1)(self.0, formatter)
+    }
+}
@@ -0,0 +1,24 @@
+// Boxing the source needs an allocator, which thiserror only pulls in through
+// its "std" feature. The derive cannot see which features are enabled, so the
+// into_source() method generated for #[error(try_from)] enums goes through
+// this macro, which reports a compile error naming the feature instead of
+// failing to resolve `Box`.
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __thiserror_into_source_method {
+    ($($method:tt)*) => {
+        $($method)*
+    };
+}
+
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __thiserror_into_source_method {
+    ($($method:tt)*) => {
+        ::core::compile_error! {
+            "#[error(try_from)] on an enum generates into_source(), which requires thiserror's \"std\" feature; put #[error(try_from)] on the variants instead"
+        }
+    };
+}
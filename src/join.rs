@@ -0,0 +1,42 @@
+use core::fmt::{self, Display};
+
+// Stands in for a field interpolated as `{field:join(", ")}`, and prints the
+// field's items with the separator between them.
+#[doc(hidden)]
+pub struct Join<'a, T: ?Sized>(pub &'a T, pub &'static str);
+
+// Collections whose items can be displayed. The derive requires this of a
+// generic field formatted with `:join(...)`, as it would require Display of
+// one interpolated as `{field}`.
+#[doc(hidden)]
+pub trait Joinable {
+    fn join(&self, separator: &str, formatter: &mut fmt::Formatter) -> fmt::Result;
+}
+
+impl<T> Joinable for T
+where
+    T: ?Sized,
+    for<'a> &'a T: IntoIterator,
+    for<'a> <&'a T as IntoIterator>::Item: Display,
+{
+    fn join(&self, separator: &str, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let mut iter = self.into_iter();
+        if let Some(first) = iter.next() {
+            first.fmt(formatter)?;
+            for item in iter {
+                formatter.write_str(separator)?;
+                item.fmt(formatter)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T> Display for Join<'a, T>
+where
+    T: ?Sized + Joinable,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        Joinable::join(self.0, self.1, formatter)
+    }
+}
@@ -100,9 +100,13 @@
 //!   attribute.
 //!
 //!   The variant using `#[from]` must not contain any other fields beyond the
-//!   source error (and possibly a backtrace &mdash; see below). Usually
-//!   `#[from]` fields are unnamed, but `#[from]` is allowed on a named field
-//!   too.
+//!   source error (and possibly a backtrace &mdash; see below), except for
+//!   fields marked `#[error(default)]` or `#[error(default = expr)]` which the
+//!   `From` impl fills in. Usually `#[from]` fields are unnamed, but `#[from]`
+//!   is allowed on a named field too. Writing `#[from(boxed)]` on a field of
+//!   type `Box<E>` or `Arc<E>` additionally generates a `From<E>` impl that
+//!   boxes the source, so that `?` works on `E` itself. This is opt-in because
+//!   it conflicts with any other `From<E>` impl for the same error type.
 //!
 //!   ```rust
 //!   # use core::fmt::{self, Display};
@@ -128,6 +132,22 @@
 //!   # }
 //!   ```
 //!
+//!   ```rust
+//!   # use std::io;
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error("failed to sync")]
+//!   pub struct SyncError {
+//!       #[from]
+//!       source: io::Error,
+//!       #[error(default)]
+//!       context: Option<String>,
+//!       #[error(default = 3)]
+//!       retries_left: u32,
+//!   }
+//!   ```
+//!
 //! - The Error trait's [`source()`] method is implemented to return whichever
 //!   field has a `#[source]` attribute or is named `source`, if any. This is
 //!   for identifying the underlying lower level error that caused your error.
@@ -251,6 +271,564 @@
 //!   }
 //!   ```
 //!
+//! - An error may be given a stable, machine-readable code with `#[error(code =
+//!   "...")]` on the struct or on every variant of the enum. This generates an
+//!   inherent `fn code(&self) -> &'static str`. Two variants of the same enum
+//!   cannot share a code.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   pub enum DatabaseError {
+//!       #[error("connection refused")]
+//!       #[error(code = "DB-0001")]
+//!       Refused,
+//!       #[error("query timed out")]
+//!       #[error(code = "DB-0002")]
+//!       Timeout,
+//!   }
+//!
+//!   assert_eq!(DatabaseError::Timeout.code(), "DB-0002");
+//!   ```
+//!
+//! - An enum may use `#[error(kind = ErrorKind)]` to additionally generate a
+//!   fieldless `Copy + Eq + Hash` enum named `ErrorKind` mirroring the
+//!   variants, along with `fn kind(&self) -> ErrorKind`. Callers can then match
+//!   on what kind of failure occurred without destructuring the payloads, like
+//!   with [`std::io::ErrorKind`]. A transparent variant may delegate to the
+//!   inner error's own kind by naming that kind's type.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error(kind = ErrorKind)]
+//!   pub enum Error {
+//!       #[error("timed out")]
+//!       Timeout,
+//!       #[error("invalid header {name}")]
+//!       InvalidHeader { name: String },
+//!       #[error(transparent)]
+//!       #[error(kind = io::ErrorKind)]
+//!       Io(#[from] io::Error),  // ErrorKind::Io(io::ErrorKind)
+//!   }
+//!   #
+//!   # let error = Error::from(io::Error::from(io::ErrorKind::NotFound));
+//!   # assert_eq!(error.kind(), ErrorKind::Io(io::ErrorKind::NotFound));
+//!   ```
+//!
+//! - With `#[error(doc)]` on a struct, a variant, or a whole enum, the display
+//!   message is taken from the first paragraph of the doc comment instead of
+//!   being written out a second time. The same `{var}` and `{0}` shorthands
+//!   apply.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error(doc)]
+//!   pub enum DataStoreError {
+//!       /// The data for key `{0}` is not available
+//!       Redaction(String),
+//!       /// Invalid header (expected {expected:?}, found {found:?})
+//!       InvalidHeader {
+//!           expected: String,
+//!           found: String,
+//!       },
+//!   }
+//!   ```
+//!
+//! - The message may also be produced by `concat!` or another macro, or named
+//!   by a path to a `&'static str` const with `#[error(fmt_expr = PATH)]`,
+//!   which is convenient when error types are generated by a macro. A `concat!`
+//!   of literals supports all the usual shorthands. Any other message is
+//!   interpreted at runtime, where only positional `{}` and `{0}` placeholders
+//!   are supported, filled in from the additional arguments.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   const NOT_FOUND: &str = "{} not found in {}";
+//!
+//!   #[derive(Error, Debug)]
+//!   pub enum Error {
+//!       #[error(concat!("invalid ", "header", ": {0}"))]
+//!       InvalidHeader(String),
+//!       #[error(fmt_expr = NOT_FOUND, .key, .table)]
+//!       NotFound { key: String, table: String },
+//!   }
+//!   #
+//!   # let error = Error::NotFound {
+//!   #     key: "k".to_owned(),
+//!   #     table: "t".to_owned(),
+//!   # };
+//!   # assert_eq!(error.to_string(), "k not found in t");
+//!   ```
+//!
+//! - The [`Report`] wrapper renders an error together with its chain of
+//!   sources, either on a single line (`a: b: c`) or with `.pretty(true)` one
+//!   source per line. Its `Debug` representation matches `Display`, so it is
+//!   suitable as the error type returned from `main`.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   # #[derive(Error, Debug)]
+//!   # #[error("...")]
+//!   # pub struct MyError;
+//!   #
+//!   # fn run() -> Result<(), MyError> {
+//!   #     Ok(())
+//!   # }
+//!   #
+//!   fn main() -> Result<(), thiserror::Report<MyError>> {
+//!       run()?;  // prints "Error: failed to load configuration: ..." on failure
+//!       Ok(())
+//!   }
+//!   ```
+//!
+//! - With `#[error(termination)]` on the struct or enum, the error implements
+//!   `std::process::Termination` and converts into `std::process::ExitCode`. On
+//!   failure the error and its sources are printed to stderr using Display
+//!   rather than Debug, and the process exits with the code given by
+//!   `#[error(exit_code = N)]` on the variant, or on the enum as a default, or
+//!   else 1. The exit code must be nonzero. It only takes effect when the error
+//!   is converted with `ExitCode::from` or used as the `Termination` value
+//!   itself; `fn main() -> Result<(), E>` still prints the error with Debug and
+//!   exits with 1.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use std::process::ExitCode;
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error(termination)]
+//!   pub enum CliError {
+//!       #[error("invalid usage: {0}")]
+//!       #[error(exit_code = 64)]
+//!       Usage(String),
+//!       #[error(transparent)]
+//!       Io(#[from] io::Error),  // exits with 1
+//!   }
+//!   #
+//!   # fn run() -> Result<(), CliError> {
+//!   #     Ok(())
+//!   # }
+//!
+//!   fn main() -> ExitCode {
+//!       match run() {
+//!           Ok(()) => ExitCode::SUCCESS,
+//!           Err(error) => error.into(),
+//!       }
+//!   }
+//!   ```
+//!
+//! - The where clauses of the generated impls are normally inferred from how
+//!   each generic field is used. Where that guess is wrong, for example with
+//!   associated types or recursive types, `#[error(bound = "...")]` replaces it
+//!   with your own predicates. On the struct or enum this replaces all inferred
+//!   bounds, and on a variant or field it replaces just the bounds inferred
+//!   from there. `#[error(bound(error = "...", display = "...", from =
+//!   "..."))]` gives the `Error`, `Display` and `From` impls separate
+//!   predicates.
+//!
+//!   ```rust
+//!   # use std::fmt::{Debug, Display};
+//!   # use thiserror::Error;
+//!   #
+//!   # pub trait Backend: Debug {
+//!   #     type Error: std::error::Error + 'static;
+//!   #     type Id: Debug + Display;
+//!   # }
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error(bound = "B::Error: std::error::Error + 'static, B::Id: Display")]
+//!   pub enum BackendError<B: Backend> {
+//!       #[error("record {0} not found")]
+//!       NotFound(B::Id),
+//!       #[error(transparent)]
+//!       Other(B::Error),
+//!   }
+//!   ```
+//!
+//! - Crates that re-export thiserror from a facade crate can point the
+//!   generated code at the re-export with `#[error(crate = path)]`, so that
+//!   downstream crates do not need their own dependency on thiserror.
+//!
+//!   ```rust
+//!   # mod my_facade {
+//!   #     pub mod thiserror {
+//!   #         pub use thiserror::*;
+//!   #     }
+//!   # }
+//!   #
+//!   #[derive(my_facade::thiserror::Error, Debug)]
+//!   #[error(crate = my_facade::thiserror)]
+//!   #[error("invalid configuration")]
+//!   pub struct ConfigError;
+//!   ```
+//!
+//! - Fields marked `#[provide]` are offered by reference through the Error
+//!   trait's [`provide()`] method, so that error reporters can request values
+//!   like request IDs or status codes from any error in the chain.
+//!   `#[provide(value)]` offers a copy of the field instead. The error's own
+//!   fields are offered before the request is forwarded to its source. Like
+//!   backtraces, this requires a nightly compiler.
+//!
+//!   ```rust
+//!   # const IGNORE: &str = stringify! {
+//!   #[derive(Error, Debug)]
+//!   #[error("request failed")]
+//!   pub struct RequestError {
+//!       #[provide]
+//!       request_id: RequestId,  // std::error::request_ref::<RequestId>(&error)
+//!       #[provide(value)]
+//!       status: StatusCode,  // std::error::request_value::<StatusCode>(&error)
+//!       source: io::Error,
+//!   }
+//!   # };
+//!   ```
+//!
+//! - A field of type `&'static Location<'static>`, or any field marked
+//!   `#[location]`, records where the error was created. Only `Location` from
+//!   `core::panic` or `std::panic`, written by name or by that path, is
+//!   detected without the attribute. The generated `From` impls are
+//!   `#[track_caller]`, so for errors converted with `?` this is the location
+//!   of the `?`. Unlike a backtrace this is nearly free to capture and works on
+//!   a stable compiler. When the error also has a backtrace or a `#[provide]`
+//!   field, which need `#![feature(error_generic_member_access)]` in your
+//!   crate, the location is provided as a `Location<'static>` through the Error
+//!   trait's [`provide()`] method as well.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use std::panic::Location;
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error("failed to read configuration")]
+//!   pub struct ConfigError {
+//!       #[from]
+//!       source: io::Error,
+//!       location: &'static Location<'static>,  // automatically detected
+//!   }
+//!   ```
+//!
+//! - One source type can be routed into several variants with `#[from(when =
+//!   predicate)]`. The predicate is a function taking a reference to the source
+//!   and returning `bool`; the generated `From` impl tries the predicates in
+//!   declaration order and falls back to the one variant with a plain `#[from]`
+//!   of that type. Types are compared as written, so spell the type the same
+//!   way in each variant; `io::Error` and `std::io::Error` count as different
+//!   types. The fallback cannot use `#[from(boxed)]`.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   pub enum ConfigError {
+//!       #[error("config file not found")]
+//!       NotFound(#[from(when = is_not_found)] io::Error),
+//!       #[error("failed to read config file")]
+//!       Io(#[from] io::Error),
+//!   }
+//!
+//!   fn is_not_found(error: &io::Error) -> bool {
+//!       error.kind() == io::ErrorKind::NotFound
+//!   }
+//!   ```
+//!
+//! - A field marked `#[error(from(A, B))]` gets a `From` impl for each listed
+//!   type instead of for its own type, converting through `Into` into the
+//!   field's type. This lets several source types land in the same variant.
+//!   Other lists in a `#[from(...)]` attribute, such as `#[from(ignore)]`, are
+//!   left alone for derive_more.
+//!
+//!   ```rust
+//!   # use std::num::{ParseFloatError, ParseIntError};
+//!   # use thiserror::Error;
+//!   #
+//!   # #[derive(Error, Debug)]
+//!   # #[error("...")]
+//!   # pub struct ParseError;
+//!   #
+//!   # impl From<ParseIntError> for ParseError {
+//!   #     fn from(_: ParseIntError) -> Self {
+//!   #         ParseError
+//!   #     }
+//!   # }
+//!   #
+//!   # impl From<ParseFloatError> for ParseError {
+//!   #     fn from(_: ParseFloatError) -> Self {
+//!   #         ParseError
+//!   #     }
+//!   # }
+//!   #
+//!   #[derive(Error, Debug)]
+//!   pub enum AppError {
+//!       #[error("invalid number")]
+//!       Parse(#[error(from(ParseIntError, ParseFloatError))] ParseError),
+//!   }
+//!   ```
+//!
+//! - The message formatting is also available on its own for types that are not
+//!   errors, such as states, commands, and identifiers. `#[derive(Display)]`
+//!   reads the same arguments from a `#[display(...)]` attribute and generates
+//!   only the [`Display`] impl, without requiring `Debug`.
+//!
+//!   ```rust
+//!   # use std::path::PathBuf;
+//!   #
+//!   #[derive(thiserror::Display)]
+//!   pub enum Command {
+//!       #[display("open {path}")]
+//!       Open { path: PathBuf },
+//!       #[display("quit")]
+//!       Quit,
+//!   }
+//!   ```
+//!
+//! - With `#[error(debug = display)]` the derive also generates the `Debug`
+//!   impl, which prints the message followed by the chain of sources. This is
+//!   what gets shown when the error is returned from `main`. A field marked
+//!   `#[error(redact)]` is rendered as `<redacted>` wherever the message
+//!   interpolates it, so secrets stay out of both [`Display`] and `Debug`
+//!   output.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error)]
+//!   #[error(debug = display)]
+//!   pub enum AuthError {
+//!       #[error("invalid token {token} for {user}")]
+//!       InvalidToken {
+//!           user: String,
+//!           #[error(redact)]
+//!           token: String,
+//!       },
+//!   }
+//!   ```
+//!
+//! - `#[error(redact = "...")]` replaces the field with custom text instead of
+//!   `<redacted>`. Building with `RUSTFLAGS='--cfg thiserror_unredact'` brings
+//!   back the real values of all redacted fields, which can help when debugging
+//!   locally. Because of this, a redacted field still needs to implement the
+//!   formatting trait that its placeholder asks for.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error("login failed for {email}")]
+//!   pub struct LoginError {
+//!       #[error(redact = "<email>")]
+//!       email: String,
+//!   }
+//!   ```
+//!
+//! - With `#[error(context)]` on an enum, every variant that has a source also
+//!   gets a context selector named after the variant with a `Ctx` suffix. The
+//!   selector holds the variant's other fields, and the [`ResultExt`] extension
+//!   trait uses it to wrap the error of a `Result`. Backtrace and location
+//!   fields are filled in automatically. Generic enums are not supported. The
+//!   selectors are defined next to the enum, so two enums with
+//!   `#[error(context)]` in the same module must not have variants of the same
+//!   name; move one of them into its own module if they do.
+//!
+//!   ```rust
+//!   # use std::fs;
+//!   # use std::io;
+//!   # use std::path::PathBuf;
+//!   use thiserror::{Error, ResultExt as _};
+//!
+//!   #[derive(Error, Debug)]
+//!   #[error(context)]
+//!   pub enum ConfigError {
+//!       #[error("failed to read {path}")]
+//!       ReadConfig { path: PathBuf, source: io::Error },
+//!   }
+//!
+//!   fn read_config(path: PathBuf) -> Result<String, ConfigError> {
+//!       fs::read_to_string(&path).context(ReadConfigCtx { path })
+//!   }
+//!   ```
+//!
+//! - With `#[error(constructors)]` on an enum, each variant gets a constructor
+//!   function named after the variant in snake case. Each parameter accepts
+//!   anything that converts `Into` the field's type. Backtrace, location, and
+//!   `#[error(default)]` fields are filled in automatically.
+//!
+//!   ```rust
+//!   # use std::path::PathBuf;
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error(constructors)]
+//!   pub enum ConfigError {
+//!       #[error("{name} not found at {path}")]
+//!       NotFound { path: PathBuf, name: String },
+//!   }
+//!
+//!   let error = ConfigError::not_found("/etc/app.toml", "config");
+//!   ```
+//!
+//! - With `#[error(try_from)]` on an enum, the payload of each `#[from]`
+//!   variant can be taken back out with `TryFrom`, which returns the original
+//!   error if it is a different variant. The enum also gets an `into_source()`
+//!   method that returns whichever source it holds as `Box<dyn Error + Send +
+//!   Sync>`. To only generate `TryFrom` for some variants, put the attribute on
+//!   those variants instead. Generic payloads and boxed trait objects are
+//!   skipped.
+//!
+//!   A payload type with its own generic `From<E>` impl, such as
+//!   `anyhow::Error`, already gets `TryFrom<AppError>` from core, so the
+//!   enum-level attribute fails to compile with conflicting implementations.
+//!   Put the attribute on the other variants instead. `into_source()` needs
+//!   trait object sources to be `Send + Sync`, returns `None` for transparent
+//!   variants since their source belongs to the error they wrap, and requires
+//!   thiserror's "std" feature.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error(try_from)]
+//!   pub enum AppError {
+//!       #[error("io error")]
+//!       Io(#[from] io::Error),
+//!       #[error("{0}")]
+//!       Message(String),
+//!   }
+//!
+//!   fn recover(error: AppError) -> Result<io::Error, AppError> {
+//!       io::Error::try_from(error)
+//!   }
+//!   ```
+//!
+//! - With `#[error(accessors)]` on an enum, the enum gets a `variant_name()`
+//!   method returning the variant's name as a `&'static str`, plus an `is_*()`
+//!   method for every variant and an `as_*()` method for every variant with
+//!   fields, named after the variant in snake case. `as_*()` returns a
+//!   reference to the field, or a tuple of references if the variant has more
+//!   than one field.
+//!
+//!   ```rust
+//!   # use std::time::Duration;
+//!   # use thiserror::Error;
+//!   #
+//!   # #[derive(Debug)]
+//!   # pub struct Timeout {
+//!   #     elapsed: Duration,
+//!   # }
+//!   #
+//!   # mod metrics {
+//!   #     pub fn increment(_: &str) {}
+//!   #     pub fn observe(_: std::time::Duration) {}
+//!   # }
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error(accessors)]
+//!   pub enum RequestError {
+//!       #[error("request timed out")]
+//!       Timeout(Timeout),
+//!       #[error("server returned {status}")]
+//!       BadStatus { status: u16 },
+//!   }
+//!
+//!   fn record(error: &RequestError) {
+//!       metrics::increment(error.variant_name());
+//!       if let Some(timeout) = error.as_timeout() {
+//!           metrics::observe(timeout.elapsed);
+//!       }
+//!   }
+//!   ```
+//!
+//! - Error reporters print the chain of sources after the top-level message, so
+//!   a source that is also interpolated into the message shows up twice. Doing
+//!   so produces a deprecation warning at the format string. If the duplication
+//!   is intended, silence it with `#[error(allow_source_in_display)]` on the
+//!   struct, variant, or enum. The warning follows the crate's `deprecated`
+//!   lint level, so `#![deny(deprecated)]` turns it into an error.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   pub enum DataStoreError {
+//!       #[error("read failed: {0}")] // warning: the source error is also printed...
+//!       Read(#[source] io::Error),
+//!       #[error("write failed")] // ok
+//!       Write(#[source] io::Error),
+//!   }
+//!   ```
+//!
+//! - With `#[error(lint_messages)]` on a struct or enum, each message is
+//!   checked against the [Rust API guidelines] for error messages: it should be
+//!   lowercase, have no trailing period or newlines, and not start with
+//!   "error:". Problems are reported as deprecation warnings pointing into the
+//!   format string. Messages derived from doc comments are not checked.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error(lint_messages)]
+//!   pub enum DataStoreError {
+//!       #[error("Key not found.")] // warning: error messages should start with a lowercase letter
+//!       NotFound,
+//!       #[error("HTTP request failed")] // ok
+//!       Http,
+//!   }
+//!   ```
+//!
+//!   [Rust API guidelines]: https://rust-lang.github.io/api-guidelines/interoperability.html#error-types-are-meaningful-and-well-behaved-c-good-err
+//!
+//! - A field marked `#[error(fmt_with = path)]` is formatted by calling
+//!   `path(&field, formatter)` wherever it appears in the message, instead of
+//!   through its `Display` or `Debug` impl. The function has the signature
+//!   `fn(&T, &mut fmt::Formatter) -> fmt::Result`. Such a field can only appear
+//!   as `{field}` or `{field:?}`, since other format specs would have no effect.
+//!
+//!   ```rust
+//!   # use std::fmt;
+//!   # use std::time::Duration;
+//!   # use thiserror::Error;
+//!   #
+//!   fn millis(duration: &Duration, formatter: &mut fmt::Formatter) -> fmt::Result {
+//!       write!(formatter, "{}ms", duration.as_millis())
+//!   }
+//!
+//!   #[derive(Error, Debug)]
+//!   #[error("request timed out after {elapsed}")]
+//!   pub struct TimeoutError {
+//!       #[error(fmt_with = millis)]
+//!       elapsed: Duration,
+//!   }
+//!   ```
+//!
+//! - A field whose reference implements `IntoIterator` with `Display` items can
+//!   be interpolated with its items joined by a separator, without collecting
+//!   into a `String` first, using `{field:join(", ")}`. The separator is taken
+//!   literally up to the next `"`. A generic field such as `Vec<T>` gets the
+//!   bound this needs inferred, the same way `{field}` infers `T: Display`.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error(r#"missing fields: {fields:join(", ")}"#)]
+//!   pub struct MissingFields {
+//!       fields: Vec<&'static str>,
+//!   }
+//!   ```
+//!
 //! - See also the [`anyhow`] library for a convenient single error type to use
 //!   in application code.
 //!
@@ -279,11 +857,22 @@ extern crate std;
 extern crate std as core;
 
 mod aserror;
+mod context;
+mod debug;
 mod display;
+mod fmt;
+mod into_source;
+mod join;
 #[cfg(error_generic_member_access)]
 mod provide;
+mod redact;
+mod report;
+#[cfg(feature = "std")]
+mod termination;
 mod var;
 
+pub use crate::context::{IntoError, ResultExt};
+pub use crate::report::Report;
 pub use thiserror_impl::*;
 
 mod private;
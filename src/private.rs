@@ -1,10 +1,25 @@
 #[doc(hidden)]
+pub use crate::__thiserror_into_source_method as into_source_method;
+#[doc(hidden)]
 pub use crate::aserror::AsDynError;
 #[doc(hidden)]
+pub use crate::context::IntoError;
+#[doc(hidden)]
+pub use crate::debug::debug_as_display;
+#[doc(hidden)]
 pub use crate::display::AsDisplay;
+#[doc(hidden)]
+pub use crate::fmt::{write_runtime_fmt, FmtWith};
+#[doc(hidden)]
+pub use crate::join::{Join, Joinable};
 #[cfg(error_generic_member_access)]
 #[doc(hidden)]
-pub use crate::provide::ThiserrorProvide;
+pub use crate::provide::{provide_location, ThiserrorProvide};
+#[doc(hidden)]
+pub use crate::redact::Redacted;
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub use crate::termination::report_exit;
 #[doc(hidden)]
 pub use crate::var::Var;
 #[doc(hidden)]
@@ -12,3 +27,9 @@ pub use core::error::Error;
 #[cfg(all(feature = "std", not(thiserror_no_backtrace_type)))]
 #[doc(hidden)]
 pub use std::backtrace::Backtrace;
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub use std::boxed::Box;
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub use std::process::{ExitCode, Termination};
@@ -1,4 +1,5 @@
 use core::error::{Error, Request};
+use core::panic::Location;
 
 #[doc(hidden)]
 pub trait ThiserrorProvide: Sealed {
@@ -18,3 +19,15 @@ where
 #[doc(hidden)]
 pub trait Sealed {}
 impl<T: Error + ?Sized> Sealed for T {}
+
+// Takes either `&'static Location<'static>` or an Option of one, including
+// through a type alias that the derive cannot see through.
+#[doc(hidden)]
+pub fn provide_location<L>(request: &mut Request, location: &L)
+where
+    L: Copy + Into<Option<&'static Location<'static>>>,
+{
+    if let Some(location) = (*location).into() {
+        request.provide_ref::<Location<'static>>(location);
+    }
+}
@@ -0,0 +1,27 @@
+use core::fmt::{
+    self, Binary, Debug, Display, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex,
+};
+
+// Stands in for a field marked #[error(redact)] wherever the field would have
+// been interpolated into the message, and prints the replacement text instead
+// unless the crate deriving Error is built with --cfg thiserror_unredact.
+#[doc(hidden)]
+pub struct Redacted<T>(pub T, pub &'static str, pub bool);
+
+macro_rules! impl_redacted {
+    ($($Trait:ident)*) => {
+        $(
+            impl<T: $Trait> $Trait for Redacted<T> {
+                fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    if self.2 {
+                        $Trait::fmt(&self.0, formatter)
+                    } else {
+                        formatter.pad(self.1)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_redacted!(Display Debug Octal LowerHex UpperHex Pointer Binary LowerExp UpperExp);
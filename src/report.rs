@@ -0,0 +1,193 @@
+use core::error::Error;
+use core::fmt::{self, Debug, Display, Write};
+
+/// An error reporter that prints an error together with its chain of sources.
+///
+/// By default the whole chain is rendered on a single line, with each source
+/// separated from the error it caused by a colon.
+///
+/// ```
+/// # use thiserror::{Error, Report};
+/// #
+/// #[derive(Error, Debug)]
+/// #[error("failed to load configuration")]
+/// pub struct ConfigError {
+///     source: std::io::Error,
+/// }
+///
+/// let source = std::io::Error::new(std::io::ErrorKind::NotFound, "config.toml not found");
+/// let error = ConfigError { source };
+/// assert_eq!(
+///     Report::new(&error).to_string(),
+///     "failed to load configuration: config.toml not found",
+/// );
+/// ```
+///
+/// With [`pretty`][Report::pretty], every source goes on a line of its own.
+///
+/// ```text
+/// failed to load configuration
+///
+/// Caused by:
+///   0: config.toml not found
+/// ```
+///
+/// The `Debug` representation of a report is the same as its `Display`
+/// representation, so a `Report` can be returned as the error type of `main`.
+///
+/// ```
+/// # use thiserror::{Error, Report};
+/// #
+/// # #[derive(Error, Debug)]
+/// # #[error("...")]
+/// # pub struct MyError;
+/// #
+/// # fn run() -> Result<(), MyError> {
+/// #     Ok(())
+/// # }
+/// #
+/// fn main() -> Result<(), Report<MyError>> {
+///     run()?;
+///     Ok(())
+/// }
+/// ```
+pub struct Report<E> {
+    error: E,
+    pretty: bool,
+    show_backtrace: bool,
+}
+
+impl<E> Report<E>
+where
+    E: Error,
+{
+    /// Creates a new report for the given error.
+    pub fn new(error: E) -> Self {
+        Report {
+            error,
+            pretty: false,
+            show_backtrace: false,
+        }
+    }
+
+    /// Renders the error and each of its sources on separate lines.
+    pub fn pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+
+    /// Appends the backtrace to a [`pretty`][Report::pretty] report, if one
+    /// was captured by the error or any of its sources and is available
+    /// through [`Error::provide`]. This requires the standard library and a
+    /// nightly compiler; otherwise no backtrace is shown.
+    pub fn show_backtrace(mut self, show_backtrace: bool) -> Self {
+        self.show_backtrace = show_backtrace;
+        self
+    }
+
+    fn fmt_singleline(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.error)?;
+        let mut source = self.error.source();
+        while let Some(error) = source {
+            write!(formatter, ": {}", error)?;
+            source = error.source();
+        }
+        Ok(())
+    }
+
+    fn fmt_multiline(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.error)?;
+        let mut source = self.error.source();
+        if source.is_some() {
+            formatter.write_str("\n\nCaused by:")?;
+        }
+        let mut index = 0;
+        while let Some(error) = source {
+            write!(formatter, "\n{:>3}: ", index)?;
+            write!(Indented(formatter), "{}", error)?;
+            source = error.source();
+            index += 1;
+        }
+        if self.show_backtrace {
+            self.fmt_backtrace(formatter)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(all(
+        feature = "std",
+        error_generic_member_access,
+        not(thiserror_no_backtrace_type),
+    ))]
+    fn fmt_backtrace(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        use std::backtrace::{Backtrace, BacktraceStatus};
+
+        let mut error: Option<&dyn Error> = Some(&self.error);
+        while let Some(current) = error {
+            if let Some(backtrace) = core::error::request_ref::<Backtrace>(current) {
+                if let BacktraceStatus::Captured = backtrace.status() {
+                    return write!(formatter, "\n\nStack backtrace:\n{}", backtrace);
+                }
+            }
+            error = current.source();
+        }
+        Ok(())
+    }
+
+    #[cfg(not(all(
+        feature = "std",
+        error_generic_member_access,
+        not(thiserror_no_backtrace_type),
+    )))]
+    #[allow(clippy::unused_self)]
+    fn fmt_backtrace(&self, _formatter: &mut fmt::Formatter) -> fmt::Result {
+        Ok(())
+    }
+}
+
+impl<E> From<E> for Report<E>
+where
+    E: Error,
+{
+    fn from(error: E) -> Self {
+        Report::new(error)
+    }
+}
+
+impl<E> Display for Report<E>
+where
+    E: Error,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if self.pretty {
+            self.fmt_multiline(formatter)
+        } else {
+            self.fmt_singleline(formatter)
+        }
+    }
+}
+
+impl<E> Debug for Report<E>
+where
+    E: Error,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(self, formatter)
+    }
+}
+
+// Aligns the continuation lines of a multi-line source message with the first
+// line, which comes after the source's index.
+struct Indented<'a, 'b>(&'a mut fmt::Formatter<'b>);
+
+impl Write for Indented<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for (i, line) in s.split('\n').enumerate() {
+            if i > 0 {
+                self.0.write_str("\n     ")?;
+            }
+            self.0.write_str(line)?;
+        }
+        Ok(())
+    }
+}
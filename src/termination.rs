@@ -0,0 +1,13 @@
+use crate::Report;
+use core::error::Error;
+use std::eprintln;
+use std::process::ExitCode;
+
+// Used by the Termination impl generated for #[error(termination)]. Prints the
+// error and its sources the way `main` would, but using Display rather than
+// Debug, and exits with the error's configured exit code.
+#[doc(hidden)]
+pub fn report_exit(error: &dyn Error, exit_code: u8) -> ExitCode {
+    eprintln!("Error: {}", Report::new(error).pretty(true));
+    ExitCode::from(exit_code)
+}
@@ -0,0 +1,83 @@
+use std::io;
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Debug)]
+pub struct Timeout {
+    pub elapsed: Duration,
+}
+
+#[derive(Error, Debug)]
+#[error(accessors)]
+pub enum RequestError {
+    #[error("request timed out")]
+    Timeout(Timeout),
+    #[error("server returned {status}: {body}")]
+    BadStatus { status: u16, body: String },
+    #[error("connection failed")]
+    Io(#[source] io::Error),
+    #[error("request cancelled")]
+    Cancelled,
+    #[error("invalid type")]
+    r#Type,
+}
+
+#[derive(Error, Debug)]
+#[error(accessors)]
+pub enum GenericError<T> {
+    #[error("wrong value")]
+    Wrong { value: T },
+}
+
+#[derive(Error, Debug)]
+#[error(accessors)]
+pub enum Never {}
+
+#[test]
+fn test_variant_name() {
+    let error = RequestError::Timeout(Timeout {
+        elapsed: Duration::from_secs(30),
+    });
+    assert_eq!("Timeout", error.variant_name());
+    assert_eq!("Cancelled", RequestError::Cancelled.variant_name());
+    assert_eq!("Type", RequestError::r#Type.variant_name());
+}
+
+#[test]
+fn test_is() {
+    let error = RequestError::Cancelled;
+    assert!(error.is_cancelled());
+    assert!(!error.is_timeout());
+    assert!(!error.is_bad_status());
+    assert!(RequestError::r#Type.is_type());
+}
+
+#[test]
+fn test_as() {
+    let error = RequestError::Timeout(Timeout {
+        elapsed: Duration::from_secs(30),
+    });
+    let timeout = error.as_timeout().unwrap();
+    assert_eq!(Duration::from_secs(30), timeout.elapsed);
+    assert!(error.as_bad_status().is_none());
+    assert!(error.as_io().is_none());
+
+    let error = RequestError::BadStatus {
+        status: 503,
+        body: "unavailable".to_owned(),
+    };
+    let (status, body) = error.as_bad_status().unwrap();
+    assert_eq!(503, *status);
+    assert_eq!("unavailable", body);
+
+    let error = RequestError::Io(io::Error::new(io::ErrorKind::Other, "..."));
+    assert_eq!(io::ErrorKind::Other, error.as_io().unwrap().kind());
+}
+
+#[test]
+fn test_generic() {
+    let error = GenericError::Wrong { value: 1 };
+    assert_eq!("Wrong", error.variant_name());
+    assert!(error.is_wrong());
+    assert_eq!(Some(&1), error.as_wrong());
+}
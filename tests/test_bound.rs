@@ -0,0 +1,96 @@
+use std::error::Error as StdError;
+use std::fmt::{self, Debug, Display};
+use std::io;
+use thiserror::Error;
+
+fn assert_error<E: StdError>() {}
+
+// Without the explicit bound, the inferred `Box<Recursive<E>>: Error + 'static`
+// overflows while checking whether Recursive<E> implements Error.
+#[derive(Error, Debug)]
+pub enum Recursive<E> {
+    #[error("leaf: {0}")]
+    Leaf(E),
+    #[error("node")]
+    Node(
+        #[source]
+        #[error(bound(error = "E: 'static"))]
+        Box<Recursive<E>>,
+    ),
+}
+
+#[test]
+fn test_recursive() {
+    assert_error::<Recursive<io::Error>>();
+
+    let leaf = Recursive::Leaf(io::Error::new(io::ErrorKind::Other, "oh no"));
+    let error = Recursive::Node(Box::new(leaf));
+    assert_eq!("node", error.to_string());
+    assert_eq!("leaf: oh no", error.source().unwrap().to_string());
+}
+
+pub trait Backend {
+    type Error: StdError + 'static;
+    type Id: Display;
+}
+
+#[derive(Debug)]
+pub struct Postgres;
+
+impl Backend for Postgres {
+    type Error = io::Error;
+    type Id = u64;
+}
+
+#[derive(Error)]
+#[error(bound = "B::Error: StdError + 'static, B::Id: Display")]
+pub enum BackendError<B: Backend> {
+    #[error("record {0} not found")]
+    NotFound(B::Id),
+    #[error(transparent)]
+    Other(B::Error),
+}
+
+impl<B: Backend> Debug for BackendError<B> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("BackendError")
+    }
+}
+
+#[test]
+fn test_associated_type() {
+    assert_error::<BackendError<Postgres>>();
+
+    let error = BackendError::<Postgres>::NotFound(1);
+    assert_eq!("record 1 not found", error.to_string());
+}
+
+#[derive(Error, Debug)]
+#[error(bound(display = "T: Display", from = "T: Send"))]
+pub enum Separate<T> {
+    #[error("invalid value {0}")]
+    Invalid(T),
+    #[error("io error")]
+    Io(#[from] io::Error),
+}
+
+#[test]
+fn test_separate() {
+    let error = Separate::<i32>::from(io::Error::new(io::ErrorKind::Other, "..."));
+    assert_eq!("io error", error.to_string());
+    assert_eq!("invalid value 1", Separate::Invalid(1).to_string());
+}
+
+#[derive(Error, Debug)]
+#[error("{message}: {value}")]
+pub struct Extended<T> {
+    message: String,
+    #[error(bound = "T: Display + Copy")]
+    value: T,
+}
+
+#[test]
+fn test_field() {
+    fn assert_display<T: Display>() {}
+    assert_display::<Extended<u8>>();
+}
@@ -0,0 +1,44 @@
+use thiserror::Error;
+
+#[test]
+fn test_struct() {
+    #[derive(Error, Debug)]
+    #[error("connection refused")]
+    #[error(code = "DB-0042")]
+    pub struct Error;
+
+    assert_eq!("DB-0042", Error.code());
+}
+
+#[test]
+fn test_enum() {
+    #[derive(Error, Debug)]
+    pub enum Error {
+        #[error("connection refused")]
+        #[error(code = "DB-0001")]
+        Refused,
+        #[error("timed out after {0}s")]
+        #[error(code = "DB-0002")]
+        Timeout(u64),
+        #[error(transparent)]
+        #[error(code = "DB-0003")]
+        Io(#[from] std::io::Error),
+    }
+
+    assert_eq!("DB-0001", Error::Refused.code());
+    assert_eq!("DB-0002", Error::Timeout(30).code());
+    let io = std::io::Error::new(std::io::ErrorKind::Other, "...");
+    assert_eq!("DB-0003", Error::from(io).code());
+}
+
+#[test]
+fn test_generic() {
+    #[derive(Error, Debug)]
+    pub enum Error<T> {
+        #[error("invalid value {0}")]
+        #[error(code = "VAL-1")]
+        Invalid(T),
+    }
+
+    assert_eq!("VAL-1", Error::Invalid(1).code());
+}
@@ -0,0 +1,53 @@
+use std::io;
+use std::panic::Location;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(constructors)]
+pub enum ConfigError {
+    #[error("{name} not found at {path}")]
+    NotFound { path: PathBuf, name: String },
+    #[error("failed to read")]
+    Read(#[source] io::Error),
+    #[error("unexpected input")]
+    HTTPResponse {
+        status: u16,
+        location: &'static Location<'static>,
+    },
+    #[error("invalid type")]
+    Type,
+    #[error("invalid")]
+    Invalid,
+}
+
+#[derive(Error, Debug)]
+#[error(constructors)]
+pub enum GenericError<T> {
+    #[error("wrong value")]
+    Wrong { value: T },
+}
+
+#[test]
+fn test_constructors() {
+    let error = ConfigError::not_found("/etc/app.toml", "config");
+    assert_eq!("config not found at /etc/app.toml", error.to_string());
+
+    let error = ConfigError::read(io::Error::new(io::ErrorKind::Other, "..."));
+    assert!(matches!(error, ConfigError::Read(_)));
+
+    let error = ConfigError::http_response(404u16);
+    match error {
+        ConfigError::HTTPResponse { status, location } => {
+            assert_eq!(404, status);
+            assert_eq!(file!(), location.file());
+        }
+        _ => panic!("expected HTTPResponse"),
+    }
+
+    assert!(matches!(ConfigError::r#type(), ConfigError::Type));
+    assert!(matches!(ConfigError::invalid(), ConfigError::Invalid));
+
+    let error = GenericError::<String>::wrong("x");
+    assert!(matches!(error, GenericError::Wrong { value } if value == "x"));
+}
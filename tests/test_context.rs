@@ -0,0 +1,59 @@
+use std::io;
+use std::panic::Location;
+use std::path::PathBuf;
+use thiserror::{Error, ResultExt as _};
+
+#[derive(Error, Debug)]
+#[error(context)]
+pub enum ConfigError {
+    #[error("failed to read {path}")]
+    ReadConfig { path: PathBuf, source: io::Error },
+    #[error("failed to parse line {0}")]
+    Parse(usize, #[source] std::num::ParseIntError),
+    #[error("lost connection")]
+    Disconnected {
+        source: io::Error,
+        location: &'static Location<'static>,
+    },
+    #[error("invalid")]
+    Invalid,
+}
+
+fn read(path: &str) -> Result<(), io::Error> {
+    Err(io::Error::new(io::ErrorKind::NotFound, path.to_owned()))
+}
+
+#[test]
+fn test_named() {
+    let error = read("config.toml")
+        .context(ReadConfigCtx {
+            path: PathBuf::from("config.toml"),
+        })
+        .unwrap_err();
+    match error {
+        ConfigError::ReadConfig { path, source } => {
+            assert_eq!(PathBuf::from("config.toml"), path);
+            assert_eq!(io::ErrorKind::NotFound, source.kind());
+        }
+        _ => panic!("expected ReadConfig"),
+    }
+}
+
+#[test]
+fn test_unnamed() {
+    let error = "x".parse::<i32>().context(ParseCtx(3)).unwrap_err();
+    assert_eq!("failed to parse line 3", error.to_string());
+}
+
+#[test]
+fn test_location() {
+    let line = line!() + 1;
+    let error = read("socket").with_context(|| DisconnectedCtx).unwrap_err();
+    match error {
+        ConfigError::Disconnected { location, .. } => {
+            assert_eq!(file!(), location.file());
+            assert_eq!(line, location.line());
+        }
+        _ => panic!("expected Disconnected"),
+    }
+}
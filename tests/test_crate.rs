@@ -0,0 +1,42 @@
+use std::error::Error as _;
+use std::io;
+
+mod facade {
+    pub use ::thiserror;
+}
+
+#[test]
+fn test_struct() {
+    #[derive(facade::thiserror::Error, Debug)]
+    #[error(crate = crate::facade::thiserror)]
+    #[error("failed to read {path}")]
+    pub struct Error {
+        path: String,
+        source: io::Error,
+    }
+
+    let error = Error {
+        path: "config.toml".to_owned(),
+        source: io::Error::new(io::ErrorKind::NotFound, "not found"),
+    };
+    assert_eq!("failed to read config.toml", error.to_string());
+    assert_eq!("not found", error.source().unwrap().to_string());
+}
+
+#[test]
+fn test_enum() {
+    const MESSAGE: &str = "invalid value {}";
+
+    #[derive(facade::thiserror::Error, Debug)]
+    #[error(crate = crate::facade::thiserror)]
+    pub enum Error {
+        #[error(fmt_expr = MESSAGE, .0)]
+        Invalid(u8),
+        #[error(transparent)]
+        Io(#[from] io::Error),
+    }
+
+    assert_eq!("invalid value 1", Error::Invalid(1).to_string());
+    let io = io::Error::new(io::ErrorKind::Other, "oh no");
+    assert_eq!("oh no", Error::from(io).to_string());
+}
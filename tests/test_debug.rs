@@ -0,0 +1,107 @@
+#![allow(clippy::uninlined_format_args)]
+
+use std::error::Error as _;
+use std::fmt::Display;
+use std::io;
+use thiserror::Error;
+
+#[derive(Error)]
+#[error(debug = display)]
+#[error("failed to load configuration")]
+pub struct ConfigError {
+    source: io::Error,
+}
+
+#[derive(Error)]
+#[error(debug = display)]
+pub enum AuthError {
+    #[error("invalid token {token} for {user}")]
+    InvalidToken {
+        user: String,
+        #[error(redact)]
+        token: String,
+    },
+    #[error("token {0:?} expired")]
+    Expired(#[error(redact)] String),
+}
+
+#[derive(Error)]
+#[error(debug = display)]
+#[error("wrapped: {0}")]
+pub struct Wrapper<T: Display>(T);
+
+#[test]
+fn test_debug_as_display() {
+    let error = ConfigError {
+        source: io::Error::new(io::ErrorKind::NotFound, "config.toml not found"),
+    };
+    assert!(error.source().is_some());
+    assert_eq!(
+        "failed to load configuration\n\nCaused by:\n  0: config.toml not found",
+        format!("{:?}", error),
+    );
+}
+
+#[test]
+fn test_redact() {
+    let error = AuthError::InvalidToken {
+        user: "ferris".to_owned(),
+        token: "hunter2".to_owned(),
+    };
+    let expected = if cfg!(thiserror_unredact) {
+        "invalid token hunter2 for ferris"
+    } else {
+        "invalid token <redacted> for ferris"
+    };
+    assert_eq!(expected, error.to_string());
+    assert_eq!(expected, format!("{:?}", error));
+
+    let error = AuthError::Expired("hunter2".to_owned());
+    let expected = if cfg!(thiserror_unredact) {
+        "token \"hunter2\" expired"
+    } else {
+        "token <redacted> expired"
+    };
+    assert_eq!(expected, error.to_string());
+}
+
+#[derive(Error, Debug)]
+#[error("login failed for {email}")]
+pub struct LoginError {
+    #[error(redact = "***")]
+    email: String,
+}
+
+#[test]
+fn test_redact_replacement() {
+    let error = LoginError {
+        email: "ferris@example.com".to_owned(),
+    };
+    let expected = if cfg!(thiserror_unredact) {
+        "login failed for ferris@example.com"
+    } else {
+        "login failed for ***"
+    };
+    assert_eq!(expected, error.to_string());
+}
+
+#[derive(Error, Debug)]
+#[error("id {0:x}, mask {1:>10b}")]
+pub struct IdError(#[error(redact)] u32, #[error(redact)] u8);
+
+#[test]
+fn test_redact_format_spec() {
+    let error = IdError(255, 5);
+    let expected = if cfg!(thiserror_unredact) {
+        "id ff, mask        101"
+    } else {
+        "id <redacted>, mask <redacted>"
+    };
+    assert_eq!(expected, error.to_string());
+}
+
+#[test]
+fn test_generic() {
+    let error = Wrapper("inner");
+    assert_eq!("wrapped: inner", format!("{:?}", error));
+}
@@ -0,0 +1,104 @@
+#![allow(clippy::uninlined_format_args)]
+
+use std::fmt::{self, Display};
+use std::path::PathBuf;
+
+fn assert<T: Display>(expected: &str, value: T) {
+    assert_eq!(expected, value.to_string());
+}
+
+#[test]
+fn test_struct() {
+    #[derive(thiserror::Display)]
+    #[display("user #{id} ({name})")]
+    struct UserId {
+        id: u32,
+        name: &'static str,
+    }
+
+    assert(
+        "user #7 (ferris)",
+        UserId {
+            id: 7,
+            name: "ferris",
+        },
+    );
+}
+
+#[test]
+fn test_tuple_and_unit() {
+    #[derive(thiserror::Display)]
+    #[display("{0}.{1}")]
+    struct Version(u8, u8);
+
+    #[derive(thiserror::Display)]
+    #[display("nothing")]
+    struct Nothing;
+
+    assert("1.2", Version(1, 2));
+    assert("nothing", Nothing);
+}
+
+#[test]
+fn test_enum() {
+    #[derive(thiserror::Display)]
+    enum Command {
+        #[display("open {path}")]
+        Open { path: PathBuf },
+        #[display("quit")]
+        Quit,
+        #[display(transparent)]
+        Other(String),
+    }
+
+    let path = PathBuf::from("/tmp/file");
+    assert("open /tmp/file", Command::Open { path });
+    assert("quit", Command::Quit);
+    assert("custom", Command::Other("custom".to_owned()));
+}
+
+#[test]
+fn test_generic() {
+    #[derive(thiserror::Display)]
+    #[display("[{0}]")]
+    struct Wrapper<T>(T);
+
+    // No Debug or Error bounds are required of the type.
+    struct NotDebug;
+
+    impl Display for NotDebug {
+        fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("inner")
+        }
+    }
+
+    assert("[inner]", Wrapper(NotDebug));
+}
+
+#[test]
+fn test_fmt() {
+    #[derive(thiserror::Display)]
+    enum State {
+        #[display(fmt = write_running)]
+        Running { pid: u32 },
+        #[display("stopped")]
+        Stopped,
+    }
+
+    fn write_running(pid: &u32, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "running as pid {}", pid)
+    }
+
+    assert("running as pid 42", State::Running { pid: 42 });
+    assert("stopped", State::Stopped);
+}
+
+#[test]
+fn test_doc() {
+    /// Waiting for input.
+    #[derive(thiserror::Display)]
+    #[display(doc)]
+    struct Idle;
+
+    assert("Waiting for input.", Idle);
+}
@@ -476,3 +476,133 @@ fn test_fmt_path_inherited() {
     assert("777", Error::I64(0o777));
     assert("...false", Error::Other(false));
 }
+
+#[test]
+fn test_concat() {
+    macro_rules! error_type {
+        ($name:ident, $what:expr) => {
+            #[derive(Error, Debug)]
+            #[error(concat!("invalid ", $what, ": {0}"))]
+            pub struct $name(usize);
+        };
+    }
+
+    error_type!(Error, "foo");
+
+    assert("invalid foo: 1", Error(1));
+}
+
+#[test]
+fn test_concat_nested() {
+    #[derive(Error, Debug)]
+    #[error(core::concat!("{id}", concat!(" ", 1, '.', 5), " ", true))]
+    pub struct Error {
+        id: &'static str,
+    }
+
+    assert("x 1.5 true", Error { id: "x" });
+}
+
+#[test]
+fn test_const_message() {
+    mod messages {
+        pub const MSG: &str = "failed to {} the {} (code {{{2}}})";
+    }
+
+    use messages::MSG;
+
+    #[derive(Error, Debug)]
+    pub enum Error {
+        #[error(fmt_expr = MSG, .0, .1, CODE)]
+        Tuple(&'static str, &'static str),
+        #[error(fmt_expr = messages::MSG, "open", .file, .code)]
+        Struct { file: &'static str, code: u8 },
+        #[error(fmt_expr = MSG)]
+        Unit,
+    }
+
+    const CODE: u8 = 7;
+
+    assert(
+        "failed to open the file (code {7})",
+        Error::Tuple("open", "file"),
+    );
+    assert(
+        "failed to open the file (code {7})",
+        Error::Struct {
+            file: "file",
+            code: 7,
+        },
+    );
+    assert("failed to {} the {} (code {{2}})", Error::Unit);
+}
+
+#[test]
+fn test_runtime_macro() {
+    #[derive(Error, Debug)]
+    #[error(concat!(env!("CARGO_PKG_NAME"), " error {}"), .0)]
+    pub struct Error(usize);
+
+    assert("thiserror error 1", Error(1));
+}
+
+#[test]
+fn test_allow_source_in_display() {
+    #[derive(Error, Debug)]
+    #[error("leaf")]
+    pub struct Leaf;
+
+    #[derive(Error, Debug)]
+    #[error("wrapper: {0}")]
+    #[error(allow_source_in_display)]
+    pub struct Wrapper(#[source] Leaf);
+
+    #[derive(Error, Debug)]
+    #[error(allow_source_in_display)]
+    pub enum Error {
+        #[error("from: {0}")]
+        From(#[from] Leaf),
+        #[error("source: {source}")]
+        Source { source: Leaf },
+    }
+
+    assert("wrapper: leaf", Wrapper(Leaf));
+    assert("from: leaf", Error::From(Leaf));
+    assert("source: leaf", Error::Source { source: Leaf });
+}
+
+#[test]
+fn test_lint_messages() {
+    #[derive(Error, Debug)]
+    #[error(lint_messages)]
+    pub enum Error {
+        #[error("failed to read {0}")]
+        Read(&'static str),
+        #[error("HTTP request failed")]
+        Http,
+        #[error("I/O error")]
+        Io,
+        #[error("interrupted...")]
+        Interrupted,
+        /// Not checked.
+        #[error(doc)]
+        Doc,
+    }
+
+    assert("failed to read config", Error::Read("config"));
+    assert("HTTP request failed", Error::Http);
+    assert("I/O error", Error::Io);
+    assert("interrupted...", Error::Interrupted);
+    assert("Not checked.", Error::Doc);
+}
+
+#[test]
+fn test_runtime_generic() {
+    const MSG: &str = "invalid value {}";
+
+    #[derive(Error, Debug)]
+    #[error(fmt_expr = MSG, .0)]
+    pub struct Error<T>(T);
+
+    assert("invalid value 1", Error(1));
+}
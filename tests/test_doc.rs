@@ -0,0 +1,73 @@
+use core::fmt::Display;
+use thiserror::Error;
+
+fn assert<T: Display>(expected: &str, value: T) {
+    assert_eq!(expected, value.to_string());
+}
+
+#[test]
+fn test_struct() {
+    /// Failed to read the configuration file {path}.
+    #[derive(Error, Debug)]
+    #[error(doc)]
+    pub struct Error {
+        path: String,
+    }
+
+    let path = "config.toml".to_owned();
+    assert(
+        "Failed to read the configuration file config.toml.",
+        Error { path },
+    );
+}
+
+#[test]
+fn test_enum() {
+    #[derive(Error, Debug)]
+    #[error(doc)]
+    pub enum Error {
+        /// Data store disconnected
+        Disconnect,
+        /// The data for key `{0}` is not available
+        Redaction(String),
+        /// Invalid header
+        /// (expected {expected:?}, found {found:?})
+        ///
+        /// This paragraph is only for rustdoc.
+        InvalidHeader { expected: String, found: String },
+        #[error("unknown data store error")]
+        Unknown,
+        #[error(transparent)]
+        Other(anyhow::Error),
+    }
+
+    assert("Data store disconnected", Error::Disconnect);
+    assert(
+        "The data for key `k` is not available",
+        Error::Redaction("k".to_owned()),
+    );
+    assert(
+        "Invalid header (expected \"a\", found \"b\")",
+        Error::InvalidHeader {
+            expected: "a".to_owned(),
+            found: "b".to_owned(),
+        },
+    );
+    assert("unknown data store error", Error::Unknown);
+    assert("other", Error::Other(anyhow::anyhow!("other")));
+}
+
+#[test]
+fn test_variant() {
+    #[derive(Error, Debug)]
+    pub enum Error {
+        /// Tuple error: {0}
+        #[error(doc)]
+        Tuple(usize),
+        #[error("unit error")]
+        Unit,
+    }
+
+    assert("Tuple error: 0", Error::Tuple(0));
+    assert("unit error", Error::Unit);
+}
@@ -0,0 +1,105 @@
+#![allow(
+    clippy::ptr_arg,
+    clippy::trivially_copy_pass_by_ref,
+    clippy::uninlined_format_args
+)]
+
+use std::fmt::{self, Display};
+use std::time::Duration;
+use thiserror::Error;
+
+fn assert<T: Display>(expected: &str, value: T) {
+    assert_eq!(expected, value.to_string());
+}
+
+fn millis(duration: &Duration, formatter: &mut fmt::Formatter) -> fmt::Result {
+    write!(formatter, "{}ms", duration.as_millis())
+}
+
+fn hex(bytes: &[u8; 3], formatter: &mut fmt::Formatter) -> fmt::Result {
+    for byte in bytes {
+        write!(formatter, "{:02x}", byte)?;
+    }
+    Ok(())
+}
+
+mod list {
+    use std::fmt;
+
+    pub fn comma_separated(items: &Vec<String>, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&items.join(", "))
+    }
+}
+
+#[test]
+fn test_struct() {
+    #[derive(Error, Debug)]
+    #[error("request timed out after {elapsed} (limit {limit:?})")]
+    pub struct Error {
+        #[error(fmt_with = millis)]
+        elapsed: Duration,
+        #[error(fmt_with = millis)]
+        limit: Duration,
+    }
+
+    assert(
+        "request timed out after 1500ms (limit 2000ms)",
+        Error {
+            elapsed: Duration::from_millis(1500),
+            limit: Duration::from_secs(2),
+        },
+    );
+}
+
+#[test]
+fn test_enum() {
+    #[derive(Error, Debug)]
+    pub enum Error {
+        #[error("bad checksum {0}")]
+        Checksum(#[error(fmt_with = hex)] [u8; 3]),
+        #[error("unknown keys: {keys}")]
+        UnknownKeys {
+            #[error(fmt_with = list::comma_separated)]
+            keys: Vec<String>,
+        },
+    }
+
+    assert("bad checksum 00ff1a", Error::Checksum([0x00, 0xff, 0x1a]));
+    assert(
+        "unknown keys: name, port",
+        Error::UnknownKeys {
+            keys: vec!["name".to_owned(), "port".to_owned()],
+        },
+    );
+}
+
+#[test]
+fn test_generic() {
+    // The field type needs no Display impl of its own.
+    struct Opaque;
+
+    impl fmt::Debug for Opaque {
+        fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("Opaque")
+        }
+    }
+
+    fn opaque<T>(_: &T, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("<opaque>")
+    }
+
+    #[derive(Error, Debug)]
+    #[error("wrapped {0}")]
+    pub struct Error<T>(#[error(fmt_with = opaque)] T);
+
+    assert("wrapped <opaque>", Error(Opaque));
+}
+
+#[test]
+fn test_derive_display() {
+    #[derive(thiserror::Display)]
+    #[display("took {0}")]
+    pub struct Elapsed(#[display(fmt_with = millis)] Duration);
+
+    assert("took 250ms", Elapsed(Duration::from_millis(250)));
+}
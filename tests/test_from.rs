@@ -1,6 +1,8 @@
 #![allow(clippy::extra_unused_type_parameters)]
 
 use std::io;
+use std::num::{ParseFloatError, ParseIntError};
+use std::panic::Location;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -62,3 +64,169 @@ fn test_from() {
     assert_impl::<ErrorEnumOptional>();
     assert_impl::<Many>();
 }
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct ErrorStructDefault {
+    #[from]
+    source: io::Error,
+    #[error(default)]
+    context: Option<String>,
+    #[error(default = 3)]
+    retries: u32,
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub enum ErrorEnumDefault<T> {
+    Test(#[from] io::Error, #[error(default)] Vec<T>),
+}
+
+#[test]
+fn test_default() {
+    let error = ErrorStructDefault::from(io::Error::new(io::ErrorKind::Other, "..."));
+    assert_eq!(None, error.context);
+    assert_eq!(3, error.retries);
+
+    let error = ErrorEnumDefault::<u8>::from(io::Error::new(io::ErrorKind::Other, "..."));
+    let ErrorEnumDefault::Test(_, list) = error;
+    assert!(list.is_empty());
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct ErrorStructBoxed {
+    #[from(boxed)]
+    source: Box<io::Error>,
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub enum ErrorEnumPointer {
+    Io(#[from(boxed)] std::sync::Arc<io::Error>),
+    Fmt(#[from(boxed)] Option<Box<std::fmt::Error>>),
+    Other(#[from] Box<dyn std::error::Error + Send + Sync>),
+}
+
+// Without #[from(boxed)], the boxed variant does not claim From<io::Error>.
+#[derive(Error, Debug)]
+#[error("...")]
+pub enum ErrorEnumUnboxed {
+    Io(#[from] io::Error),
+    Boxed(#[from] Box<io::Error>),
+}
+
+#[test]
+fn test_boxed() {
+    assert_impl::<ErrorStructBoxed>();
+    assert_impl::<ErrorEnumPointer>();
+    assert_impl::<ErrorEnumUnboxed>();
+
+    fn assert_impl_boxed<T: From<Box<io::Error>>>() {}
+    assert_impl_boxed::<ErrorStructBoxed>();
+    assert_impl_boxed::<ErrorEnumUnboxed>();
+
+    let error = ErrorEnumPointer::from(std::fmt::Error);
+    assert!(matches!(error, ErrorEnumPointer::Fmt(Some(_))));
+
+    let error = ErrorEnumUnboxed::from(io::Error::new(io::ErrorKind::Other, "..."));
+    assert!(matches!(error, ErrorEnumUnboxed::Io(_)));
+}
+
+#[derive(Error, Debug)]
+pub enum ErrorEnumRouted {
+    #[error("not found")]
+    NotFound(#[from(when = is_not_found)] io::Error),
+    #[error("permission denied")]
+    PermissionDenied {
+        #[from(when = is_permission_denied)]
+        source: io::Error,
+    },
+    #[error("io error")]
+    Other(#[from] io::Error),
+}
+
+fn is_not_found(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::NotFound
+}
+
+fn is_permission_denied(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::PermissionDenied
+}
+
+#[test]
+fn test_when() {
+    assert_impl::<ErrorEnumRouted>();
+
+    let error = ErrorEnumRouted::from(io::Error::from(io::ErrorKind::NotFound));
+    assert!(matches!(error, ErrorEnumRouted::NotFound(_)));
+
+    let error = ErrorEnumRouted::from(io::Error::from(io::ErrorKind::PermissionDenied));
+    assert!(matches!(error, ErrorEnumRouted::PermissionDenied { .. }));
+
+    let error = ErrorEnumRouted::from(io::Error::from(io::ErrorKind::Interrupted));
+    assert!(matches!(error, ErrorEnumRouted::Other(_)));
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct ParseError {
+    message: String,
+}
+
+impl From<ParseIntError> for ParseError {
+    fn from(error: ParseIntError) -> Self {
+        ParseError {
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<ParseFloatError> for ParseError {
+    fn from(error: ParseFloatError) -> Self {
+        ParseError {
+            message: error.to_string(),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct ErrorStructMulti {
+    #[error(from(ParseIntError, ParseFloatError))]
+    source: ParseError,
+    location: &'static Location<'static>,
+}
+
+#[derive(Error, Debug)]
+pub enum ErrorEnumMulti {
+    #[error("parse error")]
+    Parse(#[error(from(ParseIntError, ParseFloatError))] ParseError),
+    #[error("io error")]
+    Io(#[from] io::Error),
+}
+
+// Any other list in #[from(...)] is assumed to be for derive_more.
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct ErrorStructForeignFrom {
+    #[from(ignore)]
+    source: io::Error,
+}
+
+#[test]
+fn test_multiple_types() {
+    fn assert_impl_multi<T: From<ParseIntError> + From<ParseFloatError>>() {}
+    assert_impl_multi::<ErrorStructMulti>();
+    assert_impl_multi::<ErrorEnumMulti>();
+
+    let error = ErrorStructMulti::from("x".parse::<i32>().unwrap_err());
+    assert_eq!("invalid digit found in string", error.source.message);
+    assert_eq!(file!(), error.location.file());
+
+    let error = ErrorEnumMulti::from("x".parse::<f64>().unwrap_err());
+    match error {
+        ErrorEnumMulti::Parse(error) => assert_eq!("invalid float literal", error.message),
+        ErrorEnumMulti::Io(_) => panic!("expected Parse"),
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::path::PathBuf;
+use thiserror::Error;
+
+fn assert<T: Display>(expected: &str, value: T) {
+    assert_eq!(expected, value.to_string());
+}
+
+#[test]
+fn test_join() {
+    #[derive(Error, Debug)]
+    #[error(r#"missing fields: {fields:join(", ")}"#)]
+    pub struct MissingFields {
+        fields: Vec<&'static str>,
+    }
+
+    assert(
+        "missing fields: a, b, c",
+        MissingFields {
+            fields: vec!["a", "b", "c"],
+        },
+    );
+    assert("missing fields: a", MissingFields { fields: vec!["a"] });
+    assert("missing fields: ", MissingFields { fields: Vec::new() });
+}
+
+#[test]
+fn test_enum() {
+    #[derive(Error, Debug)]
+    pub enum Error {
+        #[error(r#"invalid ports {0:join("/")}"#)]
+        Ports(BTreeSet<u16>),
+        #[error(r#"{count} conflicts: {names:join(" and ")}"#)]
+        Conflict { count: usize, names: [String; 2] },
+    }
+
+    assert(
+        "invalid ports 80/443",
+        Error::Ports(BTreeSet::from([443, 80])),
+    );
+    assert(
+        "2 conflicts: x and y",
+        Error::Conflict {
+            count: 2,
+            names: ["x".to_owned(), "y".to_owned()],
+        },
+    );
+}
+
+#[test]
+fn test_multiple() {
+    #[derive(Error, Debug)]
+    #[error(r#"{dirs:join(":")} ({dirs:join(", ")})"#)]
+    pub struct Error {
+        dirs: Vec<String>,
+    }
+
+    let dirs = vec!["/bin".to_owned(), "/usr/bin".to_owned()];
+    assert("/bin:/usr/bin (/bin, /usr/bin)", Error { dirs });
+}
+
+#[test]
+fn test_display_items() {
+    pub struct Paths(Vec<PathBuf>);
+
+    impl<'a> IntoIterator for &'a Paths {
+        type Item = std::path::Display<'a>;
+        type IntoIter =
+            std::iter::Map<std::slice::Iter<'a, PathBuf>, fn(&PathBuf) -> std::path::Display>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.0.iter().map(|path| path.display())
+        }
+    }
+
+    impl std::fmt::Debug for Paths {
+        fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            self.0.fmt(formatter)
+        }
+    }
+
+    #[derive(Error, Debug)]
+    #[error(r#"not found in {0:join(", ")}"#)]
+    pub struct NotFound(Paths);
+
+    let paths = Paths(vec![PathBuf::from("/etc"), PathBuf::from("/usr/etc")]);
+    assert("not found in /etc, /usr/etc", NotFound(paths));
+}
+
+#[test]
+fn test_generic() {
+    #[derive(Error, Debug)]
+    #[error(r#"invalid values {values:join(", ")}"#)]
+    pub struct Invalid<T> {
+        values: Vec<T>,
+    }
+
+    #[derive(Error, Debug)]
+    pub enum Error<T> {
+        #[error(r#"unexpected {0:join(" or ")}"#)]
+        Unexpected(BTreeSet<T>),
+    }
+
+    assert("invalid values 1, 2", Invalid { values: vec![1, 2] });
+    assert(
+        "unexpected a or b",
+        Error::Unexpected(BTreeSet::from(["a", "b"])),
+    );
+}
+
+#[test]
+fn test_redacted() {
+    #[derive(Error, Debug)]
+    #[error(r#"ids {ids:join(", ")} as {ids:?}"#)]
+    pub struct Error {
+        #[error(redact)]
+        ids: Vec<u32>,
+    }
+
+    let expected = if cfg!(thiserror_unredact) {
+        "ids 1, 2 as [1, 2]"
+    } else {
+        "ids <redacted> as <redacted>"
+    };
+    assert(expected, Error { ids: vec![1, 2] });
+}
@@ -0,0 +1,63 @@
+use std::collections::HashSet;
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(kind = InnerErrorKind)]
+pub enum InnerError {
+    #[error("inner a")]
+    A,
+    #[error("inner b {0}")]
+    B(usize),
+}
+
+#[derive(Error, Debug)]
+#[error(kind = ErrorKind)]
+pub enum Error {
+    /// The operation timed out.
+    #[error("timed out")]
+    Timeout,
+    #[error("invalid header {name}")]
+    InvalidHeader { name: String },
+    #[error(transparent)]
+    #[error(kind = io::ErrorKind)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    #[error(kind = InnerErrorKind)]
+    Inner(#[from] InnerError),
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+#[test]
+fn test_kind() {
+    assert_eq!(ErrorKind::Timeout, Error::Timeout.kind());
+
+    let error = Error::InvalidHeader {
+        name: "Host".to_owned(),
+    };
+    assert_eq!(ErrorKind::InvalidHeader, error.kind());
+
+    let error = Error::Other(anyhow::anyhow!("..."));
+    assert_eq!(ErrorKind::Other, error.kind());
+}
+
+#[test]
+fn test_transparent() {
+    let error = Error::from(io::Error::new(io::ErrorKind::NotFound, "..."));
+    assert_eq!(ErrorKind::Io(io::ErrorKind::NotFound), error.kind());
+
+    let error = Error::from(InnerError::B(1));
+    assert_eq!(ErrorKind::Inner(InnerErrorKind::B), error.kind());
+}
+
+#[test]
+fn test_traits() {
+    fn assert_traits<T: Copy + Eq + std::hash::Hash + std::fmt::Debug>() {}
+    assert_traits::<ErrorKind>();
+
+    let mut set = HashSet::new();
+    set.insert(ErrorKind::Timeout);
+    set.insert(Error::Timeout.kind());
+    assert_eq!(1, set.len());
+}
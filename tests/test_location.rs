@@ -0,0 +1,131 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+use std::io;
+use std::panic::Location;
+use thiserror::Error;
+
+type CallerLocation = Option<&'static Location<'static>>;
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct StructError {
+    #[from]
+    source: io::Error,
+    location: &'static Location<'static>,
+}
+
+#[derive(Error, Debug)]
+pub enum EnumError {
+    #[error("...")]
+    Io {
+        #[from]
+        source: io::Error,
+        location: &'static Location<'static>,
+    },
+    #[error("...")]
+    Fmt(#[from] std::fmt::Error, #[location] CallerLocation),
+}
+
+fn io_error() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "oh no")
+}
+
+#[test]
+fn test_struct() {
+    let line = line!() + 2;
+    fn convert() -> Result<(), StructError> {
+        Err(io_error())?;
+        Ok(())
+    }
+
+    let error = convert().unwrap_err();
+    assert_eq!(file!(), error.location.file());
+    assert_eq!(line, error.location.line());
+}
+
+#[test]
+fn test_enum() {
+    let line = line!() + 1;
+    let error = EnumError::from(io_error());
+    match error {
+        EnumError::Io { location, .. } => {
+            assert_eq!(file!(), location.file());
+            assert_eq!(line, location.line());
+        }
+        EnumError::Fmt(..) => unreachable!(),
+    }
+
+    let line = line!() + 1;
+    let error = EnumError::from(std::fmt::Error);
+    match error {
+        EnumError::Fmt(_, Some(location)) => assert_eq!(line, location.line()),
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(thiserror_nightly_testing)]
+pub mod provide {
+    use std::backtrace::Backtrace;
+    use std::error;
+    use std::io;
+    use std::panic::Location;
+    use thiserror::Error;
+
+    #[derive(Error, Debug)]
+    #[error("...")]
+    pub struct WithBacktrace {
+        #[from]
+        source: io::Error,
+        backtrace: Backtrace,
+        location: &'static Location<'static>,
+    }
+
+    #[derive(Error, Debug)]
+    pub enum ProvidedLocation {
+        #[error("...")]
+        Io(#[from] io::Error, #[provide] &'static Location<'static>),
+    }
+
+    #[derive(Error, Debug)]
+    #[error("...")]
+    pub struct LocationOnly {
+        #[from]
+        source: io::Error,
+        location: &'static Location<'static>,
+    }
+
+    #[derive(Error, Debug)]
+    pub enum EnumWithBacktrace {
+        #[error("...")]
+        Io(#[from] io::Error, &'static Location<'static>),
+        #[error("...")]
+        Fmt(#[from] std::fmt::Error, Backtrace),
+    }
+
+    #[test]
+    fn test_provide() {
+        let line = line!() + 1;
+        let error = WithBacktrace::from(super::io_error());
+        let location = error::request_ref::<Location>(&error).unwrap();
+        assert_eq!(line, location.line());
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
+
+        let line = line!() + 1;
+        let error = ProvidedLocation::from(super::io_error());
+        let location = error::request_ref::<Location>(&error).unwrap();
+        assert_eq!(line, location.line());
+
+        let line = line!() + 1;
+        let error = EnumWithBacktrace::from(super::io_error());
+        let location = error::request_ref::<Location>(&error).unwrap();
+        assert_eq!(line, location.line());
+
+        let error = EnumWithBacktrace::from(std::fmt::Error);
+        assert!(error::request_ref::<Location>(&error).is_none());
+
+        // Without a backtrace or #[provide] field there is no provide() to
+        // put the location in.
+        let error = LocationOnly::from(super::io_error());
+        assert!(error::request_ref::<Location>(&error).is_none());
+    }
+}
@@ -0,0 +1,110 @@
+#![cfg(thiserror_nightly_testing)]
+#![feature(error_generic_member_access)]
+
+use std::error::{self, Error};
+use thiserror::Error;
+
+#[derive(Debug, PartialEq)]
+pub struct RequestId(String);
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StatusCode(u16);
+
+#[derive(Error, Debug)]
+#[error("request failed")]
+pub struct StructError {
+    #[provide]
+    request_id: RequestId,
+    #[provide(value)]
+    status: StatusCode,
+}
+
+#[derive(Error, Debug)]
+pub enum EnumError {
+    #[error("not found")]
+    NotFound {
+        #[provide(value)]
+        status: StatusCode,
+        #[provide]
+        path: Option<String>,
+    },
+    #[error("upstream failed")]
+    Upstream {
+        #[provide(value)]
+        status: StatusCode,
+        source: StructError,
+    },
+    #[error("unknown")]
+    Unknown,
+}
+
+#[derive(Error, Debug)]
+#[error("generic")]
+pub struct GenericError<T: std::fmt::Debug> {
+    #[provide]
+    detail: T,
+}
+
+#[test]
+fn test_struct() {
+    let error = StructError {
+        request_id: RequestId("a1b2".to_owned()),
+        status: StatusCode(503),
+    };
+    let request_id = error::request_ref::<RequestId>(&error).unwrap();
+    assert_eq!(RequestId("a1b2".to_owned()), *request_id);
+    let status = error::request_value::<StatusCode>(&error).unwrap();
+    assert_eq!(StatusCode(503), status);
+}
+
+#[test]
+fn test_enum() {
+    let error = EnumError::NotFound {
+        status: StatusCode(404),
+        path: Some("/index.html".to_owned()),
+    };
+    assert_eq!(
+        StatusCode(404),
+        error::request_value::<StatusCode>(&error).unwrap(),
+    );
+    assert_eq!("/index.html", error::request_ref::<String>(&error).unwrap());
+
+    let error = EnumError::NotFound {
+        status: StatusCode(404),
+        path: None,
+    };
+    assert!(error::request_ref::<String>(&error).is_none());
+
+    assert!(error::request_value::<StatusCode>(&EnumError::Unknown).is_none());
+}
+
+#[test]
+fn test_source_forwarding() {
+    let error = EnumError::Upstream {
+        status: StatusCode(502),
+        source: StructError {
+            request_id: RequestId("c3d4".to_owned()),
+            status: StatusCode(503),
+        },
+    };
+
+    // The outer error's own value takes precedence over the source's.
+    assert_eq!(
+        StatusCode(502),
+        error::request_value::<StatusCode>(&error).unwrap(),
+    );
+    assert_eq!(
+        StatusCode(503),
+        error::request_value::<StatusCode>(error.source().unwrap()).unwrap(),
+    );
+
+    // Values only the source has are forwarded.
+    let request_id = error::request_ref::<RequestId>(&error).unwrap();
+    assert_eq!(RequestId("c3d4".to_owned()), *request_id);
+}
+
+#[test]
+fn test_generic() {
+    let error = GenericError { detail: 7u8 };
+    assert_eq!(7, *error::request_ref::<u8>(&error).unwrap());
+}
@@ -0,0 +1,96 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+use std::io;
+use thiserror::{Error, Report};
+
+#[derive(Error, Debug)]
+#[error("failed to load configuration")]
+pub struct ConfigError {
+    source: ReadError,
+}
+
+#[derive(Error, Debug)]
+#[error("failed to read {path}")]
+pub struct ReadError {
+    path: &'static str,
+    source: io::Error,
+}
+
+fn error() -> ConfigError {
+    ConfigError {
+        source: ReadError {
+            path: "config.toml",
+            source: io::Error::new(io::ErrorKind::NotFound, "no such file\nor directory"),
+        },
+    }
+}
+
+#[test]
+fn test_singleline() {
+    let report = Report::new(error());
+    let expected =
+        "failed to load configuration: failed to read config.toml: no such file\nor directory";
+    assert_eq!(expected, report.to_string());
+    assert_eq!(expected, format!("{:?}", report));
+}
+
+#[test]
+fn test_multiline() {
+    let report = Report::new(error()).pretty(true);
+    let expected = "\
+failed to load configuration
+
+Caused by:
+  0: failed to read config.toml
+  1: no such file
+     or directory";
+    assert_eq!(expected, report.to_string());
+}
+
+#[test]
+fn test_no_source() {
+    let error = io::Error::new(io::ErrorKind::Other, "oh no!");
+    assert_eq!("oh no!", Report::new(&error).pretty(true).to_string());
+    assert_eq!("oh no!", Report::new(&error).to_string());
+}
+
+#[test]
+fn test_from() {
+    fn run() -> Result<(), Report<ConfigError>> {
+        Err(error())?;
+        Ok(())
+    }
+
+    let report = run().unwrap_err().show_backtrace(true);
+    assert_eq!(
+        "failed to load configuration: failed to read config.toml: no such file\nor directory",
+        report.to_string(),
+    );
+}
+
+#[cfg(thiserror_nightly_testing)]
+#[test]
+fn test_backtrace() {
+    use std::backtrace::Backtrace;
+
+    #[derive(Error, Debug)]
+    #[error("...")]
+    pub struct InnerError {
+        backtrace: Backtrace,
+    }
+
+    #[derive(Error, Debug)]
+    #[error("outer")]
+    pub struct OuterError {
+        source: InnerError,
+    }
+
+    let error = OuterError {
+        source: InnerError {
+            backtrace: Backtrace::force_capture(),
+        },
+    };
+    let report = Report::new(error).pretty(true).show_backtrace(true);
+    let expected = "outer\n\nCaused by:\n  0: ...\n\nStack backtrace:\n";
+    assert!(report.to_string().starts_with(expected));
+}
@@ -0,0 +1,90 @@
+use std::process::{ExitCode, Termination};
+use thiserror::Error;
+
+#[test]
+fn test_struct() {
+    #[derive(Error, Debug)]
+    #[error("configuration file not found")]
+    #[error(termination)]
+    #[error(exit_code = 78)]
+    pub struct Error;
+
+    assert_eq!(ExitCode::from(78), Error.report());
+    assert_eq!(ExitCode::from(78), ExitCode::from(Error));
+}
+
+#[test]
+fn test_enum() {
+    #[derive(Error, Debug)]
+    #[error(termination)]
+    pub enum Error {
+        #[error("invalid usage")]
+        #[error(exit_code = 2)]
+        Usage,
+        #[error("connection refused")]
+        #[error(exit_code = 69)]
+        Unavailable { port: u16 },
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+
+    assert_eq!(ExitCode::from(2), Error::Usage.report());
+    let unavailable = Error::Unavailable { port: 80 };
+    assert_eq!(ExitCode::from(69), unavailable.report());
+    let io = std::io::Error::new(std::io::ErrorKind::Other, "...");
+    assert_eq!(ExitCode::FAILURE, Error::from(io).report());
+}
+
+#[test]
+fn test_enum_default() {
+    #[derive(Error, Debug)]
+    #[error(termination)]
+    #[error(exit_code = 70)]
+    pub enum Error {
+        #[error("invalid usage")]
+        #[error(exit_code = 64)]
+        Usage,
+        #[error("internal error")]
+        Internal,
+    }
+
+    assert_eq!(ExitCode::from(64), Error::Usage.report());
+    assert_eq!(ExitCode::from(70), Error::Internal.report());
+}
+
+#[test]
+fn test_generic() {
+    #[derive(Error, Debug)]
+    #[error(termination)]
+    pub enum Error<T> {
+        #[error("invalid value {0}")]
+        #[error(exit_code = 65)]
+        Invalid(T),
+    }
+
+    fn assert_termination<T: Termination>() {}
+    assert_termination::<Error<String>>();
+    assert_eq!(ExitCode::from(65), Error::Invalid(0).report());
+}
+
+#[test]
+fn test_main() {
+    #[derive(Error, Debug)]
+    #[error("failed to load configuration")]
+    #[error(termination)]
+    #[error(exit_code = 78)]
+    pub struct Error;
+
+    fn run() -> Result<(), Error> {
+        Err(Error)
+    }
+
+    fn main() -> ExitCode {
+        match run() {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => ExitCode::from(error),
+        }
+    }
+
+    assert_eq!(ExitCode::from(78), main());
+}
@@ -0,0 +1,73 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(try_from)]
+pub enum AppError {
+    #[error("io error")]
+    Io(#[from] io::Error),
+    #[error("fmt error")]
+    Fmt {
+        #[from]
+        source: std::fmt::Error,
+    },
+    #[error("other")]
+    Other(#[from] Box<dyn std::error::Error + Send + Sync>),
+    #[error("message: {0}")]
+    Message(String),
+}
+
+#[derive(Error, Debug)]
+#[error(try_from)]
+pub enum Generic<E> {
+    #[error("wrapped")]
+    Wrapped(#[source] E),
+    #[error(transparent)]
+    Transparent(io::Error),
+}
+
+#[derive(Error, Debug)]
+pub enum Selective {
+    #[error("io error")]
+    #[error(try_from)]
+    Io(#[from] io::Error),
+    #[error("fmt error")]
+    Fmt(#[from] std::fmt::Error),
+}
+
+#[test]
+fn test_try_from() {
+    let error = AppError::from(io::Error::new(io::ErrorKind::Other, "oh no"));
+    let io_error = io::Error::try_from(error).unwrap();
+    assert_eq!("oh no", io_error.to_string());
+
+    let error = AppError::Message("...".to_owned());
+    let error = io::Error::try_from(error).unwrap_err();
+    assert!(matches!(error, AppError::Message(_)));
+
+    let error = std::fmt::Error::try_from(AppError::from(std::fmt::Error));
+    assert!(error.is_ok());
+
+    let error = Selective::from(io::Error::new(io::ErrorKind::Other, "oh no"));
+    assert!(io::Error::try_from(error).is_ok());
+}
+
+#[test]
+fn test_into_source() {
+    let error = AppError::from(io::Error::new(io::ErrorKind::Other, "oh no"));
+    let source = error.into_source().unwrap();
+    assert!(source.downcast_ref::<io::Error>().is_some());
+
+    let error = AppError::Message("...".to_owned());
+    assert!(error.into_source().is_none());
+}
+
+#[test]
+fn test_into_source_generic() {
+    let error = Generic::Wrapped(io::Error::new(io::ErrorKind::Other, "oh no"));
+    let source = error.into_source().unwrap();
+    assert!(source.downcast_ref::<io::Error>().is_some());
+
+    let error = Generic::<io::Error>::Transparent(io::Error::new(io::ErrorKind::Other, "oh no"));
+    assert!(error.into_source().is_none());
+}
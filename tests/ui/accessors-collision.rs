@@ -0,0 +1,12 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(accessors)]
+pub enum Error {
+    #[error("...")]
+    Http,
+    #[error("...")]
+    HTTP,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("failed to read: {source}")]
+pub struct Error {
+    #[error(allow_source_in_display)]
+    source: io::Error,
+}
+
+fn main() {}
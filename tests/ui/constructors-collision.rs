@@ -0,0 +1,12 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(constructors)]
+pub enum Error {
+    #[error("...")]
+    HttpError,
+    #[error("...")]
+    HTTPError,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(context)]
+pub enum Error<E> {
+    #[error("failed")]
+    Failed { source: E },
+}
+
+fn main() {}
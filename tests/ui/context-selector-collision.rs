@@ -0,0 +1,18 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(context)]
+pub enum ConfigError {
+    #[error("failed to read config")]
+    Read { source: io::Error },
+}
+
+#[derive(Error, Debug)]
+#[error(context)]
+pub enum CacheError {
+    #[error("failed to read cache")]
+    Read { source: io::Error },
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("invalid value")]
+    #[error(crate = thiserror)]
+    Invalid,
+}
+
+fn main() {}
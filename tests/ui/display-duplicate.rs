@@ -0,0 +1,11 @@
+#[derive(thiserror::Display)]
+#[display("...")]
+#[display("...")]
+pub struct DuplicateFmt;
+
+#[derive(thiserror::Display)]
+#[display(transparent)]
+#[display(transparent)]
+pub struct DuplicateTransparent(String);
+
+fn main() {}
@@ -0,0 +1,8 @@
+#[derive(thiserror::Display)]
+pub enum State {
+    #[display("running")]
+    Running,
+    Stopped,
+}
+
+fn main() {}
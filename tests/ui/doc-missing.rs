@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(doc)]
+pub enum Error {
+    /// Documented variant
+    Documented,
+    Undocumented,
+}
+
+fn main() {}
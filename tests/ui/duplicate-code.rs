@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("connection refused")]
+    #[error(code = "DB-0001")]
+    Refused,
+    #[error("timed out")]
+    #[error(code = "DB-0001")]
+    Timeout,
+}
+
+fn main() {}
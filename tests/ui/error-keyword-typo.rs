@@ -0,0 +1,7 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(trasparent)]
+pub struct Error(std::io::Error);
+
+fn main() {}
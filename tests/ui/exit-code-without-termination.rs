@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("invalid usage")]
+    #[error(exit_code = 64)]
+    Usage,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(termination)]
+pub enum Error {
+    #[error("done")]
+    #[error(exit_code = 0)]
+    Done,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use std::fmt;
+use thiserror::Error;
+
+fn hex(id: &u32, formatter: &mut fmt::Formatter) -> fmt::Result {
+    write!(formatter, "{:#x}", id)
+}
+
+#[derive(Error, Debug)]
+#[error("id {0:x}")]
+pub struct Error(#[error(fmt_with = hex)] u32);
+
+fn main() {}
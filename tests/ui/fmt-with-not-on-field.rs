@@ -0,0 +1,13 @@
+use std::fmt;
+use thiserror::Error;
+
+fn millis(_: &u64, formatter: &mut fmt::Formatter) -> fmt::Result {
+    formatter.write_str("...")
+}
+
+#[derive(Error, Debug)]
+#[error("timed out after {0}")]
+#[error(fmt_with = millis)]
+pub struct Error(u64);
+
+fn main() {}
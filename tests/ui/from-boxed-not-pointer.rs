@@ -0,0 +1,18 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct ErrorStruct {
+    #[from(boxed)]
+    source: io::Error,
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct ErrorTraitObject {
+    #[from(boxed)]
+    source: Box<dyn std::error::Error + Send + Sync>,
+}
+
+fn main() {}
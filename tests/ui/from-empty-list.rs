@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct Error {
+    #[error(from())]
+    source: std::io::Error,
+}
+
+fn main() {}
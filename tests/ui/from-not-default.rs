@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct Error {
+    #[from]
+    source: std::io::Error,
+    #[error(default)]
+    context: Option<String>,
+    retries: u32,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("not found")]
+    NotFound(#[from(when = is_not_found)] Box<io::Error>),
+    #[error("io error")]
+    Io(#[from(boxed)] Box<io::Error>),
+}
+
+fn is_not_found(error: &Box<io::Error>) -> bool {
+    error.kind() == io::ErrorKind::NotFound
+}
+
+fn main() {}
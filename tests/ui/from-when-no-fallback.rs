@@ -0,0 +1,20 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("not found")]
+    NotFound(#[from(when = is_not_found)] io::Error),
+    #[error("permission denied")]
+    PermissionDenied(#[from(when = is_permission_denied)] io::Error),
+}
+
+fn is_not_found(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::NotFound
+}
+
+fn is_permission_denied(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::PermissionDenied
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("missing fields: {0:join(, )}")]
+pub struct Error(Vec<String>);
+
+fn main() {}
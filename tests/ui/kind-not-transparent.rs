@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(kind = ErrorKind)]
+pub enum Error {
+    #[error("io error")]
+    #[error(kind = std::io::ErrorKind)]
+    Io(std::io::Error),
+}
+
+fn main() {}
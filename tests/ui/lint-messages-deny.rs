@@ -0,0 +1,12 @@
+#![deny(deprecated)]
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(lint_messages)]
+pub enum Error {
+    #[error("Key not found.\n")]
+    KeyNotFound,
+}
+
+fn main() {}
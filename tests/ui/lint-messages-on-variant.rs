@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(lint_messages)]
+    #[error("failed")]
+    Failed,
+}
+
+fn main() {}
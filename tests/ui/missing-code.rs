@@ -0,0 +1,12 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("connection refused")]
+    #[error(code = "DB-0001")]
+    Refused,
+    #[error("timed out")]
+    Timeout,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[provide]
+pub struct Error {
+    status: u16,
+}
+
+fn main() {}
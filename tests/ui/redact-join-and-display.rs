@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("ids {ids:join(\", \")} all {ids}")]
+pub struct Error {
+    #[error(redact)]
+    ids: Vec<u32>,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(redact)]
+#[error("...")]
+pub struct Error {
+    token: String,
+}
+
+fn main() {}
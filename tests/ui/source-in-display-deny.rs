@@ -0,0 +1,18 @@
+#![deny(deprecated)]
+
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum EnumError {
+    #[error("failed to read: {0}")]
+    Read(#[from] io::Error),
+}
+
+#[derive(Error, Debug)]
+#[error("failed to write: {source:?}")]
+pub struct StructError {
+    source: io::Error,
+}
+
+fn main() {}
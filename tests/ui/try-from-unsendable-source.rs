@@ -0,0 +1,12 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(try_from)]
+pub enum Error {
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+    #[error("other")]
+    Other(#[source] Box<dyn std::error::Error>),
+}
+
+fn main() {}